@@ -0,0 +1,147 @@
+use aoc_common::input::INPUTS_DIR;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Known-correct answers kept next to the puzzle inputs, e.g.
+///
+/// ```toml
+/// [day_1]
+/// part_1 = 142
+/// part_2 = "281"
+/// ```
+pub struct Answers {
+    path: PathBuf,
+    table: Table,
+}
+
+impl Answers {
+    pub fn default_path() -> PathBuf {
+        Path::new(INPUTS_DIR).join("answers.toml")
+    }
+
+    /// A missing file is treated as an empty set of answers.
+    pub fn load(path: &Path) -> Result<Answers, AnswersError> {
+        let table = match fs::read_to_string(path) {
+            Ok(content) => Answers::parse(&content).map_err(|error| AnswersError::Parse {
+                path: path.to_path_buf(),
+                error,
+            })?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Table::new(),
+            Err(error) => {
+                return Err(AnswersError::Io {
+                    path: path.to_path_buf(),
+                    error,
+                })
+            }
+        };
+
+        Ok(Answers {
+            path: path.to_path_buf(),
+            table,
+        })
+    }
+
+    fn parse(content: &str) -> Result<Table, toml::de::Error> {
+        content.parse::<Table>()
+    }
+
+    pub fn expected(&self, day: u8, part: u8) -> Option<String> {
+        match self.table.get(&day_key(day))?.get(part_key(part))? {
+            Value::String(answer) => Some(answer.clone()),
+            Value::Integer(answer) => Some(answer.to_string()),
+            _ => None,
+        }
+    }
+
+    pub fn record(&mut self, day: u8, part: u8, answer: &str) {
+        let day = self
+            .table
+            .entry(day_key(day))
+            .or_insert_with(|| Value::Table(Table::new()));
+        if let Value::Table(parts) = day {
+            let value = match answer.parse::<i64>() {
+                Ok(number) => Value::Integer(number),
+                Err(_) => Value::String(answer.to_string()),
+            };
+            parts.insert(part_key(part).to_string(), value);
+        }
+    }
+
+    pub fn save(&self) -> Result<(), AnswersError> {
+        fs::write(&self.path, self.table.to_string()).map_err(|error| AnswersError::Io {
+            path: self.path.clone(),
+            error,
+        })
+    }
+}
+
+fn day_key(day: u8) -> String {
+    format!("day_{}", day)
+}
+
+fn part_key(part: u8) -> &'static str {
+    match part {
+        1 => "part_1",
+        _ => "part_2",
+    }
+}
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        error: toml::de::Error,
+    },
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnswersError::Io { path, error } => {
+                write!(f, "cannot access {}: {}", path.display(), error)
+            }
+            AnswersError::Parse { path, error } => {
+                write!(f, "invalid answers file {}: {}", path.display(), error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AnswersError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(content: &str) -> Answers {
+        Answers {
+            path: PathBuf::from("answers.toml"),
+            table: Answers::parse(content).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_expected_accepts_numbers_and_strings() {
+        let answers = answers("[day_1]\npart_1 = 142\npart_2 = \"281\"\n");
+
+        assert_eq!(answers.expected(1, 1), Some("142".to_string()));
+        assert_eq!(answers.expected(1, 2), Some("281".to_string()));
+        assert_eq!(answers.expected(2, 1), None);
+    }
+
+    #[test]
+    fn test_recorded_answer_is_expected() {
+        let mut answers = answers("");
+        answers.record(4, 2, "30");
+
+        assert_eq!(answers.expected(4, 2), Some("30".to_string()));
+        assert_eq!(answers.table.to_string(), "[day_4]\npart_2 = 30\n");
+    }
+}
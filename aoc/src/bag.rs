@@ -0,0 +1,115 @@
+use aoc_common::diagnostic::Diagnose;
+use aoc_common::input::InputSource;
+use day_2::bag::{self, BagConfig};
+use day_2::part_1;
+use day_2::simulate::{Replacement, Simulator};
+use std::error::Error;
+use std::path::Path;
+use std::process::ExitCode;
+
+/// Checks the day 2 games against `limits` (or the bag in `config`) on top of
+/// the puzzle's bag, listing what `raise` would additionally allow.
+pub fn report(
+    input: Option<&str>,
+    config: Option<&Path>,
+    limits: Option<&str>,
+    raise: Option<&str>,
+) -> ExitCode {
+    match check(input, config, limits, raise) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn check(
+    input: Option<&str>,
+    config: Option<&Path>,
+    limits: Option<&str>,
+    raise: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let mut bag = BagConfig::puzzle();
+    if let Some(path) = config {
+        bag = bag.merged(&BagConfig::from_file(path)?);
+    }
+    if let Some(limits) = limits {
+        bag = bag.merged(&limits.parse()?);
+    }
+
+    let source = InputSource::from_arg(2, input);
+    let parsed = day_2::parse(&source.read()?)?;
+    for warning in &parsed.warnings {
+        eprintln!(
+            "Warning: {}",
+            warning.diagnostic().in_file(source.to_string())
+        );
+    }
+    let games = parsed.value;
+
+    let valid: Vec<_> = games
+        .iter()
+        .filter(|game| game.valid(&bag))
+        .map(|game| game.id())
+        .collect();
+    println!("Bag: {}", bag);
+    println!("Valid games: {}", join(&valid));
+    println!("Sum of ids: {}", part_1::sum_of_valid_ids(&games, &bag));
+
+    if let Some(raise) = raise {
+        let raised = bag.merged(&raise.parse()?);
+        let ids: Vec<_> = bag::newly_valid(&games, &bag, &raised)
+            .map(|game| game.id())
+            .collect();
+        println!("Also valid with {}: {}", raised, join(&ids));
+    }
+    Ok(())
+}
+
+/// Prints `games` simulated games, drawn from the puzzle's bag with `bag`
+/// overriding it.
+pub fn simulate(
+    games: usize,
+    seed: u64,
+    bag: Option<&str>,
+    without_replacement: bool,
+    max_rounds: usize,
+) -> ExitCode {
+    let mut limits = BagConfig::puzzle();
+    if let Some(bag) = bag {
+        match bag.parse() {
+            Ok(overrides) => limits = limits.merged(&overrides),
+            Err(error) => {
+                eprintln!("Error: {}", error);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let replacement = match without_replacement {
+        true => Replacement::Without,
+        false => Replacement::With,
+    };
+
+    let mut simulator = match Simulator::new(limits, seed) {
+        Ok(simulator) => simulator.replacement(replacement).max_rounds(max_rounds),
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    for id in 1..=games {
+        println!("{}", simulator.game(id));
+    }
+    ExitCode::SUCCESS
+}
+
+fn join(ids: &[usize]) -> String {
+    if ids.is_empty() {
+        return "none".to_string();
+    }
+    ids.iter()
+        .map(usize::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,175 @@
+use answers::Answers;
+use aoc_common::input::InputSource;
+use aoc_common::report::{self, Format, PartResult};
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+mod alloc;
+mod answers;
+mod bag;
+mod query;
+mod solutions;
+mod timing;
+mod trace;
+mod verify;
+
+#[global_allocator]
+static ALLOCATOR: alloc::CountingAllocator = alloc::CountingAllocator;
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2023 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the solution for a single day
+    Run {
+        /// Day of the puzzle, e.g. 3
+        day: u8,
+        /// Run only one part of the puzzle; both parts are run when omitted
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Puzzle input file, `-` for stdin; defaults to inputs/day_<day>.txt
+        input: Option<String>,
+        /// Output format, `text` or `json` (one object per part and line)
+        #[arg(long, default_value = "text")]
+        format: Format,
+        /// Show the first and last digit found on every line (day 1 only)
+        #[arg(long)]
+        trace: bool,
+    },
+    /// Run every day against its input and compare with the recorded answers
+    Verify {
+        /// Answers file; defaults to inputs/answers.toml
+        #[arg(long)]
+        answers: Option<PathBuf>,
+        /// Store the results of parts that have no recorded answer yet
+        #[arg(long)]
+        record: bool,
+    },
+    /// Check the day 2 games against a bag with different cube limits
+    Bag {
+        /// Puzzle input file, `-` for stdin; defaults to inputs/day_2.txt
+        input: Option<String>,
+        /// Bag config file with `colour = count` lines
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Cube limits overriding the puzzle's, e.g. `red=20,blue=3`
+        #[arg(long)]
+        limits: Option<String>,
+        /// Also list the games that become valid with these limits raised,
+        /// e.g. `blue=20`
+        #[arg(long)]
+        raise: Option<String>,
+    },
+    /// Filter or aggregate the day 2 games, e.g. `max green where id in 10..50`
+    Query {
+        /// Query such as `any(blue > red)` or `sum power where draws > 3`
+        query: String,
+        /// Puzzle input file, `-` for stdin; defaults to inputs/day_2.txt
+        input: Option<String>,
+    },
+    /// Print seeded random day 2 games drawn from a bag
+    Simulate {
+        /// Number of games
+        #[arg(long, default_value_t = 100)]
+        games: usize,
+        /// Seed of the random generator; the same seed prints the same games
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// Bag contents overriding the puzzle's, e.g. `red=20,yellow=3`
+        #[arg(long)]
+        bag: Option<String>,
+        /// Keep drawn cubes out of the bag until the game ends
+        #[arg(long)]
+        without_replacement: bool,
+        /// Most draws per game
+        #[arg(long, default_value_t = 6)]
+        max_rounds: usize,
+    },
+    /// Report wall time and peak allocation of parsing and both parts
+    Time {
+        /// Day to time; every day with an input is timed when omitted
+        day: Option<u8>,
+        /// Number of runs to report min/median/max over
+        #[arg(long, default_value_t = 1)]
+        repeat: usize,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Run {
+            day,
+            part,
+            input,
+            format,
+            trace,
+        } => {
+            let parts = match part {
+                Some(part) => vec![part],
+                None => vec![1, 2],
+            };
+            if trace {
+                trace::report(day, &parts, input.as_deref(), format)
+            } else {
+                run(day, &parts, input.as_deref(), format)
+            }
+        }
+        Command::Verify { answers, record } => {
+            let path = answers.unwrap_or_else(Answers::default_path);
+            match Answers::load(&path) {
+                Ok(mut answers) => verify::verify(&mut answers, record),
+                Err(error) => {
+                    eprintln!("Error: {}", error);
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        Command::Bag {
+            input,
+            config,
+            limits,
+            raise,
+        } => bag::report(
+            input.as_deref(),
+            config.as_deref(),
+            limits.as_deref(),
+            raise.as_deref(),
+        ),
+        Command::Query { query, input } => query::report(&query, input.as_deref()),
+        Command::Simulate {
+            games,
+            seed,
+            bag,
+            without_replacement,
+            max_rounds,
+        } => bag::simulate(games, seed, bag.as_deref(), without_replacement, max_rounds),
+        Command::Time { day, repeat } => match day {
+            Some(day) => timing::report(&[day], repeat),
+            None => timing::report(&solutions::DAYS, repeat),
+        },
+    }
+}
+
+fn run(day: u8, parts: &[u8], input: Option<&str>, format: Format) -> ExitCode {
+    if !solutions::DAYS.contains(&day) {
+        let error = format!("day {} is not solved yet", day);
+        return report::print(&[PartResult::failed(day, parts[0], error)], format);
+    }
+
+    let source = InputSource::from_arg(day, input);
+    let input = match source.read() {
+        Ok(input) => input,
+        Err(error) => return report::print(&[PartResult::failed(day, parts[0], error)], format),
+    };
+
+    let results = solutions::run(day, parts, &input, &source.to_string()).unwrap_or_default();
+    report::print(&results, format)
+}
@@ -0,0 +1,42 @@
+use aoc_common::diagnostic::Diagnose;
+use aoc_common::input::InputSource;
+use day_2::query;
+use std::process::ExitCode;
+
+/// Evaluates a day 2 query, see [`day_2::query`] for the language.
+pub fn report(query_text: &str, input: Option<&str>) -> ExitCode {
+    let source = InputSource::from_arg(2, input);
+    let input = match source.read() {
+        Ok(input) => input,
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    let games = match day_2::parse(&input) {
+        Ok(parsed) => {
+            for warning in &parsed.warnings {
+                eprintln!(
+                    "Warning: {}",
+                    warning.diagnostic().in_file(source.to_string())
+                );
+            }
+            parsed.value
+        }
+        Err(error) => {
+            eprintln!("{}", error.diagnostic().in_file(source.to_string()));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match query::query(&games, query_text) {
+        Ok(result) => {
+            println!("{}", result);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{}", error.diagnostic().in_file("query"));
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use aoc_common::report::{self, PartResult};
+use aoc_common::Solution;
+use day_1::Day1;
+use day_2::Day2;
+use day_3::Day3;
+use day_4::Day4;
+
+pub const DAYS: [u8; 4] = [Day1::DAY, Day2::DAY, Day3::DAY, Day4::DAY];
+
+/// Work done against a solution picked by day at runtime.
+pub trait DayVisitor {
+    type Output;
+
+    fn visit<S: Solution>(self) -> Self::Output;
+}
+
+pub fn with_day<V: DayVisitor>(day: u8, visitor: V) -> Option<V::Output> {
+    let output = match day {
+        Day1::DAY => visitor.visit::<Day1>(),
+        Day2::DAY => visitor.visit::<Day2>(),
+        Day3::DAY => visitor.visit::<Day3>(),
+        Day4::DAY => visitor.visit::<Day4>(),
+        _ => return None,
+    };
+    Some(output)
+}
+
+/// [`report::run`] for the solution of `day`.
+pub fn run(day: u8, parts: &[u8], input: &str, file: &str) -> Option<Vec<PartResult>> {
+    with_day(day, Run { parts, input, file })
+}
+
+struct Run<'a> {
+    parts: &'a [u8],
+    input: &'a str,
+    file: &'a str,
+}
+
+impl DayVisitor for Run<'_> {
+    type Output = Vec<PartResult>;
+
+    fn visit<S: Solution>(self) -> Vec<PartResult> {
+        report::run::<S>(self.parts, self.input, self.file)
+    }
+}
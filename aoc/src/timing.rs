@@ -0,0 +1,207 @@
+use crate::alloc;
+use crate::solutions::{self, DayVisitor};
+use aoc_common::diagnostic::Diagnose;
+use aoc_common::input::{InputError, InputSource};
+use aoc_common::report;
+use aoc_common::Solution;
+use std::error::Error;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+pub struct Timing {
+    pub stage: &'static str,
+    pub answer: Option<String>,
+    pub durations: Vec<Duration>,
+    pub peak_bytes: usize,
+}
+
+impl Timing {
+    fn new(stage: &'static str) -> Timing {
+        Timing {
+            stage,
+            answer: None,
+            durations: Vec::new(),
+            peak_bytes: 0,
+        }
+    }
+
+    fn record(&mut self, duration: Duration, peak_bytes: usize) {
+        self.durations.push(duration);
+        self.peak_bytes = self.peak_bytes.max(peak_bytes);
+    }
+
+    /// Minimum, median and maximum of the recorded durations.
+    pub fn spread(&self) -> (Duration, Duration, Duration) {
+        let mut durations = self.durations.clone();
+        durations.sort();
+        match durations.as_slice() {
+            [] => Default::default(),
+            sorted => (
+                sorted[0],
+                sorted[sorted.len() / 2],
+                sorted[sorted.len() - 1],
+            ),
+        }
+    }
+}
+
+/// Parses the input and solves both parts `repeat` times, timing each stage.
+pub fn profile(
+    day: u8,
+    input: &str,
+    file: &str,
+    repeat: usize,
+) -> Option<Result<Vec<Timing>, Box<dyn Error>>> {
+    solutions::with_day(
+        day,
+        Profile {
+            input,
+            file,
+            repeat,
+        },
+    )
+}
+
+struct Profile<'a> {
+    input: &'a str,
+    file: &'a str,
+    repeat: usize,
+}
+
+impl DayVisitor for Profile<'_> {
+    type Output = Result<Vec<Timing>, Box<dyn Error>>;
+
+    fn visit<S: Solution>(self) -> Self::Output {
+        let mut parse = Timing::new("parse");
+        let mut parts = [Timing::new("part 1"), Timing::new("part 2")];
+
+        for _ in 0..self.repeat.max(1) {
+            let (parsed, duration, peak) = measure(|| S::parse(self.input));
+            parse.record(duration, peak);
+            let parsed = parsed.map_err(|error| error.diagnostic().in_file(self.file))?;
+
+            for (part, timing) in (1..).zip(parts.iter_mut()) {
+                let (answer, duration, peak) = measure(|| report::solve_part::<S>(&parsed, part));
+                let answer = answer.expect("every day has parts 1 and 2");
+                timing.record(duration, peak);
+                let answer = answer.map_err(|error| error.diagnostic().in_file(self.file))?;
+                timing.answer = Some(answer.to_string());
+            }
+        }
+
+        let [part_1, part_2] = parts;
+        Ok(vec![parse, part_1, part_2])
+    }
+}
+
+fn measure<T>(f: impl FnOnce() -> T) -> (T, Duration, usize) {
+    let baseline = alloc::reset_peak();
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    (result, elapsed, alloc::peak_since(baseline))
+}
+
+/// Prints a timing table for `days`, skipping days without an input file.
+pub fn report(days: &[u8], repeat: usize) -> ExitCode {
+    let mut status = ExitCode::SUCCESS;
+
+    println!(
+        "{:>3}  {:<6}  {:>16}  {:>10}  {:>10}  {:>10}  {:>10}",
+        "day", "stage", "answer", "min", "median", "max", "peak alloc"
+    );
+    for &day in days {
+        let source = InputSource::from_arg(day, None);
+        let input = match source.read() {
+            Ok(input) => input,
+            Err(InputError::NotFound(_)) => continue,
+            Err(error) => {
+                eprintln!("Error: {}", error);
+                status = ExitCode::FAILURE;
+                continue;
+            }
+        };
+
+        match profile(day, &input, &source.to_string(), repeat) {
+            Some(Ok(timings)) => {
+                for timing in timings {
+                    let (min, median, max) = timing.spread();
+                    println!(
+                        "{:>3}  {:<6}  {:>16}  {:>10}  {:>10}  {:>10}  {:>10}",
+                        day,
+                        timing.stage,
+                        timing.answer.unwrap_or_default(),
+                        format!("{:.2?}", min),
+                        format!("{:.2?}", median),
+                        format!("{:.2?}", max),
+                        format_bytes(timing.peak_bytes)
+                    );
+                }
+            }
+            Some(Err(error)) => {
+                eprintln!("Error day {}: {}", day, error);
+                status = ExitCode::FAILURE;
+            }
+            None => {
+                eprintln!("Error: day {} is not solved yet", day);
+                status = ExitCode::FAILURE;
+            }
+        }
+    }
+    status
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{} B", bytes),
+        _ => format!("{:.1} {}", value, UNITS[unit]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spread_of_odd_number_of_runs() {
+        let mut timing = Timing::new("parse");
+        for millis in [5, 1, 3] {
+            timing.record(Duration::from_millis(millis), 0);
+        }
+
+        assert_eq!(
+            timing.spread(),
+            (
+                Duration::from_millis(1),
+                Duration::from_millis(3),
+                Duration::from_millis(5)
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    fn test_profile_reports_answers_for_every_stage() {
+        let timings = profile(1, "1abc2\ntreb7uchet", "<input>", 3)
+            .unwrap()
+            .unwrap();
+
+        let stages: Vec<_> = timings.iter().map(|timing| timing.stage).collect();
+        assert_eq!(stages, ["parse", "part 1", "part 2"]);
+        assert_eq!(timings[1].answer.as_deref(), Some("89"));
+        assert!(timings.iter().all(|timing| timing.durations.len() == 3));
+    }
+}
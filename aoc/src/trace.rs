@@ -0,0 +1,45 @@
+use aoc_common::input::InputSource;
+use aoc_common::report::Format;
+use day_1::trace::{self, LineTrace};
+use day_1::vocabulary::DigitVocabulary;
+use day_1::{part_1, part_2, Numerals};
+use std::process::ExitCode;
+
+/// Prints what every line of the day 1 input contributes to each part.
+pub fn report(day: u8, parts: &[u8], input: Option<&str>, format: Format) -> ExitCode {
+    if day != 1 {
+        eprintln!("Error: --trace is only available for day 1");
+        return ExitCode::FAILURE;
+    }
+
+    let source = InputSource::from_arg(day, input);
+    let input = match source.read() {
+        Ok(input) => input,
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for &part in parts {
+        let traces = match part {
+            1 => part_1::trace(&input, Numerals::Ascii),
+            _ => part_2::trace(&input, &DigitVocabulary::english()),
+        };
+        match format {
+            Format::Text => {
+                println!("Day {} part {}:", day, part);
+                print!("{}", trace::table(&traces));
+            }
+            Format::Json => {
+                for line in traces.iter().map(LineTrace::to_json) {
+                    let mut line = line;
+                    line["day"] = day.into();
+                    line["part"] = part.into();
+                    println!("{}", line);
+                }
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
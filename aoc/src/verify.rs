@@ -0,0 +1,201 @@
+use crate::answers::Answers;
+use crate::solutions;
+use aoc_common::input::{InputError, InputSource};
+use aoc_common::report::PartResult;
+use std::process::ExitCode;
+
+enum Status {
+    Pass,
+    Fail,
+    Error,
+    Recorded,
+    Unknown,
+    NoInput,
+    /// An answer is recorded but the input to check it against is gone.
+    MissingInput,
+}
+
+impl Status {
+    fn label(&self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Fail => "FAIL",
+            Status::Error => "ERROR",
+            Status::Recorded => "recorded",
+            Status::Unknown => "no answer",
+            Status::NoInput => "no input",
+            Status::MissingInput => "NO INPUT",
+        }
+    }
+
+    fn failed(&self) -> bool {
+        matches!(self, Status::Fail | Status::Error | Status::MissingInput)
+    }
+}
+
+struct Row {
+    day: u8,
+    part: u8,
+    expected: String,
+    actual: String,
+    status: Status,
+}
+
+/// Runs every solved day against its default input and compares the results
+/// with the recorded answers. With `record` unknown answers are stored.
+pub fn verify(answers: &mut Answers, record: bool) -> ExitCode {
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+
+    for day in solutions::DAYS {
+        let source = InputSource::from_arg(day, None);
+        let input = match source.read() {
+            Ok(input) => Some(input),
+            Err(InputError::NotFound(_)) => None,
+            Err(error) => {
+                errors.push(error.to_string());
+                None
+            }
+        };
+
+        let results = match &input {
+            Some(input) => solutions::run(day, &[1, 2], input, &source.to_string())
+                .expect("every solved day can be run"),
+            None => Vec::new(),
+        };
+
+        for part in [1, 2] {
+            let expected = answers.expected(day, part);
+            let result = results.iter().find(|result| result.part == part);
+            let status = status(result, expected.as_deref(), record);
+            let actual = match (result, &status) {
+                (None, _) => String::new(),
+                (Some(result), Status::Error) => {
+                    let error = result.error.as_deref().unwrap_or_default();
+                    errors.push(format!("day {} part {}: {}", day, part, error));
+                    "-".to_string()
+                }
+                (Some(result), _) => result.answer.clone().unwrap_or_default(),
+            };
+            if let Status::Recorded = status {
+                answers.record(day, part, &actual);
+            }
+            rows.push(Row {
+                day,
+                part,
+                expected: expected.unwrap_or_default(),
+                actual,
+                status,
+            });
+        }
+    }
+
+    print_table(&rows);
+    for error in &errors {
+        eprintln!("Error: {}", error);
+    }
+
+    if record
+        && rows
+            .iter()
+            .any(|row| matches!(row.status, Status::Recorded))
+    {
+        if let Err(error) = answers.save() {
+            eprintln!("Error: {}", error);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if rows.iter().any(|row| row.status.failed()) || !errors.is_empty() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// How `result` compares with the `expected` answer; `result` is `None`
+/// when there is no input to run the part on.
+fn status(result: Option<&PartResult>, expected: Option<&str>, record: bool) -> Status {
+    let Some(result) = result else {
+        return match expected {
+            Some(_) => Status::MissingInput,
+            None => Status::NoInput,
+        };
+    };
+    match (result.answer.as_deref(), expected) {
+        (Some(actual), Some(expected)) if actual == expected => Status::Pass,
+        (Some(_), Some(_)) => Status::Fail,
+        (Some(_), None) if record => Status::Recorded,
+        (Some(_), None) => Status::Unknown,
+        (None, _) => Status::Error,
+    }
+}
+
+fn print_table(rows: &[Row]) {
+    let expected_width = rows
+        .iter()
+        .map(|row| row.expected.len())
+        .chain(["expected".len()])
+        .max()
+        .unwrap_or_default();
+    let actual_width = rows
+        .iter()
+        .map(|row| row.actual.len())
+        .chain(["actual".len()])
+        .max()
+        .unwrap_or_default();
+
+    println!(
+        "{:>3}  {:>4}  {:<expected_width$}  {:<actual_width$}  status",
+        "day", "part", "expected", "actual"
+    );
+    for row in rows {
+        println!(
+            "{:>3}  {:>4}  {:<expected_width$}  {:<actual_width$}  {}",
+            row.day,
+            row.part,
+            row.expected,
+            row.actual,
+            row.status.label()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn result(answer: Option<&str>) -> PartResult {
+        PartResult {
+            day: 1,
+            part: 1,
+            answer: answer.map(str::to_string),
+            duration: Duration::ZERO,
+            error: answer.is_none().then(|| "no digit".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_status_of_a_run_part() {
+        let cases = [
+            (Some("42"), Some("42"), false, "pass"),
+            (Some("41"), Some("42"), false, "FAIL"),
+            (None, Some("42"), false, "ERROR"),
+            (Some("42"), None, true, "recorded"),
+            (Some("42"), None, false, "no answer"),
+        ];
+
+        for (answer, expected, record, label) in cases {
+            let status = status(Some(&result(answer)), expected, record);
+
+            assert_eq!(status.label(), label, "{:?} {:?}", answer, expected);
+        }
+    }
+
+    #[test]
+    fn test_missing_input_fails_only_with_a_recorded_answer() {
+        assert!(status(None, Some("42"), false).failed());
+        assert!(!status(None, None, true).failed());
+    }
+}
@@ -0,0 +1,19 @@
+pub use crate::random::Lcg;
+use crate::Solution;
+use criterion::Criterion;
+use std::hint::black_box;
+
+/// Benchmarks parsing and both parts separately; the parts run on an input
+/// parsed once up front so they measure solving only.
+pub fn bench_solution<S: Solution>(c: &mut Criterion, name: &str, input: &str) {
+    let parsed = match S::parse(input) {
+        Ok(parsed) => parsed,
+        Err(error) => panic!("benchmark input `{}` does not parse: {}", name, error),
+    };
+
+    let mut group = c.benchmark_group(format!("day_{}/{}", S::DAY, name));
+    group.bench_function("parse", |b| b.iter(|| S::parse(black_box(input))));
+    group.bench_function("part_1", |b| b.iter(|| S::part_one(black_box(&parsed))));
+    group.bench_function("part_2", |b| b.iter(|| S::part_two(black_box(&parsed))));
+    group.finish();
+}
@@ -0,0 +1,127 @@
+use std::fmt;
+
+/// A parse error report pointing at the offending part of the puzzle input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    message: String,
+    file: Option<String>,
+    location: Option<Location>,
+    hint: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Location {
+    line: usize,
+    column: usize,
+    width: usize,
+    source: String,
+}
+
+/// Errors that can explain themselves as a [`Diagnostic`].
+pub trait Diagnose: std::error::Error {
+    fn diagnostic(&self) -> Diagnostic;
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            message: message.into(),
+            file: None,
+            location: None,
+            hint: None,
+        }
+    }
+
+    /// Points the caret at `width` characters starting at the 1-based `column`
+    /// of `source`, the full text of the 1-based `line`.
+    pub fn at(mut self, line: usize, column: usize, width: usize, source: &str) -> Diagnostic {
+        self.location = Some(Location {
+            line,
+            column,
+            width: width.max(1),
+            source: source.to_string(),
+        });
+        self
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Diagnostic {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    pub fn in_file(mut self, file: impl Into<String>) -> Diagnostic {
+        self.file = Some(file.into());
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        let file = self.file.as_deref().unwrap_or("<input>");
+
+        if let Some(location) = &self.location {
+            let gutter = " ".repeat(location.line.to_string().len());
+            write!(
+                f,
+                "\n{}--> {}:{}:{}",
+                gutter, file, location.line, location.column
+            )?;
+            write!(f, "\n{} |", gutter)?;
+            write!(f, "\n{} | {}", location.line, location.source)?;
+            write!(
+                f,
+                "\n{} | {}{}",
+                gutter,
+                " ".repeat(location.column.saturating_sub(1)),
+                "^".repeat(location.width)
+            )?;
+            if let Some(hint) = &self.hint {
+                write!(f, "\n{} = hint: {}", gutter, hint)?;
+            }
+        } else {
+            if self.file.is_some() {
+                write!(f, "\n --> {}", file)?;
+            }
+            if let Some(hint) = &self.hint {
+                write!(f, "\n = hint: {}", hint)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_snippet_with_caret_and_hint() {
+        let diagnostic = Diagnostic::new("invalid number")
+            .at(3, 14, 2, "Card 3: 23 | 6x 9")
+            .with_hint("expected `Card <id>: <numbers> | <numbers>`")
+            .in_file("inputs/day_4.txt");
+
+        let expected = "invalid number
+ --> inputs/day_4.txt:3:14
+  |
+3 | Card 3: 23 | 6x 9
+  |              ^^
+  = hint: expected `Card <id>: <numbers> | <numbers>`";
+
+        assert_eq!(diagnostic.to_string(), expected);
+    }
+
+    #[test]
+    fn test_render_without_location() {
+        let diagnostic = Diagnostic::new("Empty input").in_file("inputs/day_3.txt");
+
+        assert_eq!(diagnostic.to_string(), "Empty input\n --> inputs/day_3.txt");
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Directory holding the puzzle inputs when no path is given explicitly.
+pub const INPUTS_DIR: &str = "inputs";
+
+#[derive(Debug, PartialEq)]
+pub enum InputSource {
+    Stdin,
+    File(PathBuf),
+}
+
+impl InputSource {
+    /// `-` selects stdin, any other argument is a file path and no argument
+    /// falls back to `inputs/day_N.txt`.
+    pub fn from_arg(day: u8, arg: Option<&str>) -> InputSource {
+        match arg {
+            Some("-") => InputSource::Stdin,
+            Some(path) => InputSource::File(PathBuf::from(path)),
+            None => InputSource::File(default_path(day)),
+        }
+    }
+
+    pub fn read(&self) -> Result<String, InputError> {
+        match self {
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(InputError::Stdin)?;
+                Ok(input)
+            }
+            InputSource::File(path) => read_file(path),
+        }
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputSource::Stdin => write!(f, "<stdin>"),
+            InputSource::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+pub fn default_path(day: u8) -> PathBuf {
+    Path::new(INPUTS_DIR).join(format!("day_{}.txt", day))
+}
+
+pub fn load(day: u8, arg: Option<&str>) -> Result<String, InputError> {
+    InputSource::from_arg(day, arg).read()
+}
+
+fn read_file(path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|error| match error.kind() {
+        io::ErrorKind::NotFound => InputError::NotFound(path.to_path_buf()),
+        _ => InputError::Io(path.to_path_buf(), error),
+    })
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    NotFound(PathBuf),
+    Io(PathBuf, io::Error),
+    Stdin(io::Error),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::NotFound(path) => write!(
+                f,
+                "input file {} not found, pass a path, `-` for stdin or save the puzzle input there",
+                path.display()
+            ),
+            InputError::Io(path, error) => write!(f, "cannot read {}: {}", path.display(), error),
+            InputError::Stdin(error) => write!(f, "cannot read stdin: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::NotFound(_) => None,
+            InputError::Io(_, error) | InputError::Stdin(error) => Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dash_selects_stdin() {
+        assert_eq!(InputSource::from_arg(3, Some("-")), InputSource::Stdin);
+    }
+
+    #[test]
+    fn test_explicit_path_is_used() {
+        assert_eq!(
+            InputSource::from_arg(3, Some("sample.txt")),
+            InputSource::File(PathBuf::from("sample.txt"))
+        );
+    }
+
+    #[test]
+    fn test_default_path_follows_day() {
+        assert_eq!(
+            InputSource::from_arg(3, None),
+            InputSource::File(PathBuf::from("inputs").join("day_3.txt"))
+        );
+    }
+
+    #[test]
+    fn test_missing_file_is_reported_with_path() {
+        let result = load(3, Some("does/not/exist.txt"));
+
+        match result {
+            Err(InputError::NotFound(path)) => {
+                assert_eq!(path, PathBuf::from("does/not/exist.txt"))
+            }
+            other => panic!("expected NotFound, got {:?}", other),
+        }
+    }
+}
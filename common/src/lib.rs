@@ -0,0 +1,57 @@
+use diagnostic::Diagnose;
+use std::fmt::Display;
+
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod diagnostic;
+pub mod input;
+pub mod random;
+pub mod report;
+
+/// 1-based column of `token` within `line`; `token` has to be a slice of `line`.
+pub fn column_of(line: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize).saturating_sub(line.as_ptr() as usize);
+    line.get(..offset)
+        .map_or(1, |prefix| prefix.chars().count() + 1)
+}
+
+/// A puzzle solution split into a parsing step shared by both parts.
+///
+/// Generic tooling (runner, benchmarks, verification) only talks to days
+/// through this trait, so the input is parsed once and then handed to each
+/// part.
+pub trait Solution {
+    /// Day of the puzzle, used to look up inputs and label results.
+    const DAY: u8;
+
+    type Parsed;
+    type Answer: Display;
+    type Error: Diagnose + 'static;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Self::Error>;
+
+    fn part_one(parsed: &Self::Parsed) -> Result<Self::Answer, Self::Error>;
+
+    fn part_two(parsed: &Self::Parsed) -> Result<Self::Answer, Self::Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_of_token_inside_line() {
+        let line = "Card 1: 41 | 83";
+        let token = &line[8..10];
+
+        assert_eq!(column_of(line, token), 9);
+    }
+
+    #[test]
+    fn test_column_counts_characters() {
+        let line = "é 12";
+        let token = &line[3..];
+
+        assert_eq!(column_of(line, token), 3);
+    }
+}
@@ -0,0 +1,250 @@
+use crate::diagnostic::Diagnose;
+use crate::input::InputSource;
+use crate::Solution;
+use serde_json::json;
+use std::env;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    /// One JSON object per line and part.
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Format, String> {
+        match format {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            other => Err(format!(
+                "unknown format `{}`, expected `text` or `json`",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartResult {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<String>,
+    /// Parsing plus solving the part.
+    pub duration: Duration,
+    pub error: Option<String>,
+}
+
+impl PartResult {
+    /// A part that could not be attempted, e.g. because the input is missing.
+    pub fn failed(day: u8, part: u8, error: impl ToString) -> PartResult {
+        PartResult {
+            day,
+            part,
+            answer: None,
+            duration: Duration::ZERO,
+            error: Some(error.to_string()),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        json!({
+            "day": self.day,
+            "part": self.part,
+            "answer": self.answer,
+            "duration_ns": self.duration.as_nanos() as u64,
+            "error": self.error,
+        })
+        .to_string()
+    }
+}
+
+/// Parses `input` once and solves the requested parts, timing each of them.
+/// `file` names the input in error reports.
+pub fn run<S: Solution>(parts: &[u8], input: &str, file: &str) -> Vec<PartResult> {
+    let start = Instant::now();
+    let parsed = S::parse(input);
+    let parse_duration = start.elapsed();
+
+    parts
+        .iter()
+        .map(|&part| {
+            let result = |answer: Option<String>, duration, error: Option<String>| PartResult {
+                day: S::DAY,
+                part,
+                answer,
+                duration,
+                error,
+            };
+            let parsed = match &parsed {
+                Ok(parsed) => parsed,
+                Err(error) => {
+                    let error = error.diagnostic().in_file(file).to_string();
+                    return result(None, parse_duration, Some(error));
+                }
+            };
+
+            let start = Instant::now();
+            let answer = solve_part::<S>(parsed, part);
+            let duration = parse_duration + start.elapsed();
+            match answer {
+                None => {
+                    let error = format!("day {} has no part {}", S::DAY, part);
+                    result(None, Duration::ZERO, Some(error))
+                }
+                Some(Ok(answer)) => result(Some(answer.to_string()), duration, None),
+                Some(Err(error)) => {
+                    let error = error.diagnostic().in_file(file).to_string();
+                    result(None, duration, Some(error))
+                }
+            }
+        })
+        .collect()
+}
+
+/// Solves `part` of an already parsed input, `None` unless it is 1 or 2.
+pub fn solve_part<S: Solution>(
+    parsed: &S::Parsed,
+    part: u8,
+) -> Option<Result<S::Answer, S::Error>> {
+    match part {
+        1 => Some(S::part_one(parsed)),
+        2 => Some(S::part_two(parsed)),
+        _ => None,
+    }
+}
+
+/// Prints answers to stdout and errors to stderr (or everything as JSON
+/// lines), failing when any part failed.
+pub fn print(results: &[PartResult], format: Format) -> ExitCode {
+    for result in results {
+        match format {
+            Format::Json => println!("{}", result.to_json()),
+            Format::Text => match (&result.answer, &result.error) {
+                (_, Some(error)) => eprintln!("Error part {}: {}", result.part, error),
+                (Some(answer), None) => println!("Result part {}: {}", result.part, answer),
+                (None, None) => {}
+            },
+        }
+    }
+
+    match results.iter().any(|result| result.error.is_some()) {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
+}
+
+/// Entry point of the per-day binaries: `[--format text|json] [input]`.
+pub fn main<S: Solution>(parts: &[u8]) -> ExitCode {
+    let mut format = Format::Text;
+    let mut input = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next().as_deref().map(Format::from_str) {
+                Some(Ok(value)) => format = value,
+                Some(Err(error)) => return usage(&error),
+                None => return usage("--format needs a value"),
+            },
+            _ if input.is_none() => input = Some(arg),
+            _ => return usage(&format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    let source = InputSource::from_arg(S::DAY, input.as_deref());
+    let input = match source.read() {
+        Ok(input) => input,
+        Err(error) => {
+            let part = parts.first().copied().unwrap_or(1);
+            return print(&[PartResult::failed(S::DAY, part, error)], format);
+        }
+    };
+
+    print(&run::<S>(parts, &input, &source.to_string()), format)
+}
+
+fn usage(error: &str) -> ExitCode {
+    eprintln!("Error: {}", error);
+    eprintln!("Usage: [--format text|json] [input]");
+    ExitCode::FAILURE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("json".parse::<Format>(), Ok(Format::Json));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+
+    #[derive(Debug)]
+    struct Never;
+
+    impl std::fmt::Display for Never {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "never")
+        }
+    }
+
+    impl std::error::Error for Never {}
+
+    impl Diagnose for Never {
+        fn diagnostic(&self) -> crate::diagnostic::Diagnostic {
+            crate::diagnostic::Diagnostic::new("never")
+        }
+    }
+
+    struct Echo;
+
+    impl Solution for Echo {
+        const DAY: u8 = 9;
+
+        type Parsed = usize;
+        type Answer = usize;
+        type Error = Never;
+
+        fn parse(input: &str) -> Result<usize, Never> {
+            Ok(input.len())
+        }
+
+        fn part_one(parsed: &usize) -> Result<usize, Never> {
+            Ok(*parsed)
+        }
+
+        fn part_two(parsed: &usize) -> Result<usize, Never> {
+            Ok(2 * parsed)
+        }
+    }
+
+    #[test]
+    fn test_run_rejects_unknown_part() {
+        let results = run::<Echo>(&[2, 3], "abc", "input");
+
+        assert_eq!(results[0].answer.as_deref(), Some("6"));
+        assert_eq!(results[1].answer, None);
+        assert_eq!(results[1].error.as_deref(), Some("day 9 has no part 3"));
+    }
+
+    #[test]
+    fn test_part_result_json() {
+        let result = PartResult {
+            day: 4,
+            part: 2,
+            answer: Some("30".to_string()),
+            duration: Duration::from_nanos(1500),
+            error: None,
+        };
+
+        assert_eq!(
+            result.to_json(),
+            r#"{"answer":"30","day":4,"duration_ns":1500,"error":null,"part":2}"#
+        );
+    }
+}
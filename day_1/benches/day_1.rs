@@ -0,0 +1,54 @@
+use aoc_common::bench::{bench_solution, Lcg};
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_1::Day1;
+
+const SAMPLE: &str = "1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+fn generate(lines: usize, seed: u64) -> String {
+    let mut rng = Lcg::new(seed);
+    let mut input = String::new();
+    for _ in 0..lines {
+        input.push(char::from(b'1' + rng.below(9) as u8));
+        for _ in 0..rng.below(40) {
+            match rng.below(4) {
+                0 => input.push(char::from(b'0' + rng.below(10) as u8)),
+                1 => input.push_str(WORDS[rng.below(9) as usize]),
+                _ => input.push(char::from(b'a' + rng.below(26) as u8)),
+            }
+        }
+        input.push('\n');
+    }
+    input
+}
+
+/// A few very long lines with words only near both ends, where part 2 should
+/// not pay for the filler in between.
+fn generate_long_lines(lines: usize, length: usize, seed: u64) -> String {
+    let mut rng = Lcg::new(seed);
+    let mut input = String::new();
+    for _ in 0..lines {
+        input.push_str(WORDS[rng.below(9) as usize]);
+        for _ in 0..length {
+            input.push(char::from(b'a' + rng.below(26) as u8));
+        }
+        input.push_str(WORDS[rng.below(9) as usize]);
+        input.push('\n');
+    }
+    input
+}
+
+fn benches(c: &mut Criterion) {
+    bench_solution::<Day1>(c, "sample", SAMPLE);
+    bench_solution::<Day1>(c, "large", &generate(100_000, 1));
+    bench_solution::<Day1>(c, "long_lines", &generate_long_lines(10, 1_000_000, 1));
+}
+
+criterion_group!(day_1, benches);
+criterion_main!(day_1);
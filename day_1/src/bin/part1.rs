@@ -1,11 +1,7 @@
-use day_1::part_1::process;
-fn main() {
-    let input = include_str!("../../input.txt");
+use aoc_common::report;
+use day_1::Day1;
+use std::process::ExitCode;
 
-    let result = process(input);
-
-    match result {
-        Ok(result) => println!("Result: {}", result),
-        Err(error) => println!("Error: {}", error),
-    }
+fn main() -> ExitCode {
+    report::main::<Day1>(&[1])
 }
@@ -1,12 +1,7 @@
-use day_1::part_2::process;
+use aoc_common::report;
+use day_1::Day1;
+use std::process::ExitCode;
 
-fn main() {
-    let input = include_str!("../../input.txt");
-
-    let result = process(input);
-
-    match result {
-        Ok(result) => println!("Result: {}", result),
-        Err(error) => println!("Error: {}", error),
-    }
+fn main() -> ExitCode {
+    report::main::<Day1>(&[2])
 }
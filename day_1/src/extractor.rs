@@ -0,0 +1,268 @@
+use crate::vocabulary::DigitVocabulary;
+use crate::{part_1, CalibrationError, Numerals};
+use std::fmt;
+
+/// Which digits of a line make up its calibration value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Extraction {
+    /// The first `k` and the last `k` digits; `FirstLast(1)` is the puzzle's
+    /// rule. Lines with fewer than `k` digits reuse them on both sides.
+    FirstLast(usize),
+    /// Every digit of the line, in order.
+    All,
+    /// The digits at the given indices; negative indices count from the end.
+    Positions(Vec<isize>),
+}
+
+impl Extraction {
+    /// The digits of the calibration value, `None` when `digits` is empty,
+    /// a position is out of range or no digit is selected at all.
+    pub fn extract(&self, digits: &[u8]) -> Option<Vec<u8>> {
+        if digits.is_empty() || self.selects_nothing() {
+            return None;
+        }
+        match self {
+            Extraction::FirstLast(k) => {
+                let k = (*k).min(digits.len());
+                Some([&digits[..k], &digits[digits.len() - k..]].concat())
+            }
+            Extraction::All => Some(digits.to_vec()),
+            Extraction::Positions(positions) => positions
+                .iter()
+                .map(|&position| digits.get(index(position, digits.len())?).copied())
+                .collect(),
+        }
+    }
+
+    /// `FirstLast(0)` and no positions would make every value empty.
+    fn selects_nothing(&self) -> bool {
+        match self {
+            Extraction::FirstLast(k) => *k == 0,
+            Extraction::All => false,
+            Extraction::Positions(positions) => positions.is_empty(),
+        }
+    }
+
+    /// The first position not among `count` digits.
+    fn out_of_range(&self, count: usize) -> Option<isize> {
+        match self {
+            Extraction::Positions(positions) => positions
+                .iter()
+                .copied()
+                .find(|&position| index(position, count).is_none_or(|index| index >= count)),
+            _ => None,
+        }
+    }
+
+    /// Sums the values of every line, scanning digits as part 1 does.
+    pub fn process_part_1(
+        &self,
+        input: &str,
+        numerals: Numerals,
+    ) -> Result<DecimalSum, CalibrationError> {
+        self.sum(input, |line| {
+            part_1::digits(line, numerals).map(|m| m.digit).collect()
+        })
+    }
+
+    /// Sums the values of every line, scanning digits and words as part 2
+    /// does.
+    pub fn process_part_2(
+        &self,
+        input: &str,
+        vocabulary: &DigitVocabulary,
+    ) -> Result<DecimalSum, CalibrationError> {
+        self.sum(input, |line| {
+            vocabulary.matches(line).map(|m| m.digit).collect()
+        })
+    }
+
+    fn sum(
+        &self,
+        input: &str,
+        digits: impl Fn(&str) -> Vec<u8>,
+    ) -> Result<DecimalSum, CalibrationError> {
+        if self.selects_nothing() {
+            return Err(CalibrationError::EmptyExtraction);
+        }
+        let mut sum = DecimalSum::default();
+        for (index, line) in input.lines().enumerate() {
+            let digits = digits(line);
+            if digits.is_empty() {
+                return Err(CalibrationError::missing_digit(index, line));
+            }
+            let value =
+                self.extract(&digits)
+                    .ok_or_else(|| CalibrationError::PositionOutOfRange {
+                        line: index + 1,
+                        position: self.out_of_range(digits.len()).unwrap_or_default(),
+                        count: digits.len(),
+                        text: line.to_string(),
+                    })?;
+            sum.add_digits(&value);
+        }
+        Ok(sum)
+    }
+}
+
+/// Index of `position` among `count` digits, counting negative positions
+/// from the end.
+fn index(position: isize, count: usize) -> Option<usize> {
+    match position < 0 {
+        true => count.checked_sub(position.unsigned_abs()),
+        false => Some(position as usize),
+    }
+}
+
+/// Non-negative sum of decimal numbers of any length.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecimalSum {
+    /// Base 10^9, least significant first, no trailing zero limbs.
+    limbs: Vec<u32>,
+}
+
+const LIMB_BASE: u32 = 1_000_000_000;
+const LIMB_DIGITS: usize = 9;
+
+impl DecimalSum {
+    /// Adds the number spelled by `digits`, most significant first.
+    pub fn add_digits(&mut self, digits: &[u8]) {
+        let mut carry = 0;
+        let mut chunks = digits.rchunks(LIMB_DIGITS);
+        let mut index = 0;
+        loop {
+            let chunk = chunks.next();
+            if chunk.is_none() && carry == 0 {
+                break;
+            }
+            let addend = chunk.map_or(0, |chunk| {
+                chunk
+                    .iter()
+                    .fold(0, |limb, &digit| limb * 10 + digit as u32)
+            });
+            if index == self.limbs.len() {
+                self.limbs.push(0);
+            }
+            let limb = self.limbs[index] + addend + carry;
+            self.limbs[index] = limb % LIMB_BASE;
+            carry = limb / LIMB_BASE;
+            index += 1;
+        }
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    /// The sum, when it fits.
+    pub fn to_u128(&self) -> Option<u128> {
+        self.limbs.iter().rev().try_fold(0u128, |value, &limb| {
+            value
+                .checked_mul(LIMB_BASE as u128)?
+                .checked_add(limb as u128)
+        })
+    }
+}
+
+impl fmt::Display for DecimalSum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut limbs = self.limbs.iter().rev();
+        match limbs.next() {
+            Some(most_significant) => write!(f, "{}", most_significant)?,
+            None => write!(f, "0")?,
+        }
+        for limb in limbs {
+            write!(f, "{:09}", limb)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part_2;
+    use rstest::*;
+
+    #[rstest]
+    #[case::puzzle(Extraction::FirstLast(1), &[1, 2, 3, 4], Some(vec![1, 4]))]
+    #[case::first_last_two(Extraction::FirstLast(2), &[1, 2, 3, 4, 5], Some(vec![1, 2, 4, 5]))]
+    #[case::single_digit(Extraction::FirstLast(2), &[7], Some(vec![7, 7]))]
+    #[case::all(Extraction::All, &[1, 0, 3], Some(vec![1, 0, 3]))]
+    #[case::positions(Extraction::Positions(vec![1, -1]), &[1, 2, 3], Some(vec![2, 3]))]
+    #[case::out_of_range(Extraction::Positions(vec![3]), &[1, 2, 3], None)]
+    #[case::before_start(Extraction::Positions(vec![-4]), &[1, 2, 3], None)]
+    #[case::no_digits(Extraction::All, &[], None)]
+    #[case::first_last_zero(Extraction::FirstLast(0), &[1, 2], None)]
+    #[case::no_positions(Extraction::Positions(vec![]), &[1, 2], None)]
+    fn test_extract(
+        #[case] extraction: Extraction,
+        #[case] digits: &[u8],
+        #[case] expected: Option<Vec<u8>>,
+    ) {
+        assert_eq!(extraction.extract(digits), expected);
+    }
+
+    #[test]
+    fn test_puzzle_rule_matches_both_parts() {
+        let input = "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2";
+        let vocabulary = DigitVocabulary::english();
+        let extraction = Extraction::FirstLast(1);
+
+        let part_1 = extraction.process_part_1(input, Numerals::Ascii);
+        assert!(part_1.is_err());
+        let part_2 = extraction.process_part_2(input, &vocabulary).unwrap();
+        assert_eq!(
+            part_2.to_u128(),
+            Some(part_2::process(input).unwrap() as u128)
+        );
+    }
+
+    #[test]
+    fn test_position_out_of_range_is_not_a_missing_digit() {
+        let result = Extraction::Positions(vec![0, 2]).process_part_1("12\n3", Numerals::Ascii);
+
+        assert!(matches!(
+            result,
+            Err(CalibrationError::PositionOutOfRange {
+                line: 1,
+                position: 2,
+                count: 2,
+                ..
+            })
+        ));
+    }
+
+    #[rstest]
+    #[case::first_last_zero(Extraction::FirstLast(0))]
+    #[case::no_positions(Extraction::Positions(vec![]))]
+    fn test_empty_extraction_is_rejected(#[case] extraction: Extraction) {
+        let result = extraction.process_part_1("12", Numerals::Ascii);
+
+        assert!(matches!(result, Err(CalibrationError::EmptyExtraction)));
+    }
+
+    #[test]
+    fn test_all_digits_overflowing_u128() {
+        let line = "9".repeat(40);
+        let input = format!("{}\n{}\nx1", line, line);
+
+        let sum = Extraction::All
+            .process_part_1(&input, Numerals::Ascii)
+            .unwrap();
+
+        assert_eq!(sum.to_u128(), None);
+        assert_eq!(sum.to_string(), format!("1{}", "9".repeat(40)));
+    }
+
+    #[test]
+    fn test_decimal_sum_carries_across_limbs() {
+        let mut sum = DecimalSum::default();
+        assert_eq!(sum.to_string(), "0");
+
+        sum.add_digits(&[9; 9]);
+        sum.add_digits(&[1]);
+        assert_eq!(sum.to_string(), "1000000000");
+        sum.add_digits(&[0, 0, 5]);
+        assert_eq!(sum.to_u128(), Some(1_000_000_005));
+    }
+}
@@ -1,2 +1,242 @@
+use aoc_common::diagnostic::{Diagnose, Diagnostic};
+use aoc_common::Solution;
+use std::fmt;
+use std::io::{self, BufRead};
+
+pub mod extractor;
 pub mod part_1;
 pub mod part_2;
+pub mod trace;
+pub mod unicode;
+pub mod vocabulary;
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    /// The lines of the calibration document.
+    type Parsed = Vec<String>;
+    type Answer = usize;
+    type Error = CalibrationError;
+
+    fn parse(input: &str) -> Result<Vec<String>, CalibrationError> {
+        Ok(input.lines().map(str::to_string).collect())
+    }
+
+    fn part_one(lines: &Vec<String>) -> Result<usize, CalibrationError> {
+        part_1::process_lines(lines)
+    }
+
+    fn part_two(lines: &Vec<String>) -> Result<usize, CalibrationError> {
+        part_2::process_lines(lines)
+    }
+}
+
+/// Which characters count as numerals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Numerals {
+    /// `0`-`9` only, as in the puzzle.
+    #[default]
+    Ascii,
+    /// Decimal digits of every script, e.g. `３` or `٣`; digit words are
+    /// matched case-insensitively.
+    Unicode,
+}
+
+impl Numerals {
+    pub fn value(self, ch: char) -> Option<u8> {
+        match self {
+            Numerals::Ascii => ch.is_ascii_digit().then(|| ch as u8 - b'0'),
+            Numerals::Unicode => unicode::decimal_value(ch),
+        }
+    }
+}
+
+/// A digit or digit word found in a line, as byte offsets into the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DigitMatch {
+    pub start: usize,
+    pub end: usize,
+    pub digit: u8,
+}
+
+/// What to do with a line that has no digit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissingDigitPolicy {
+    /// Fail with the line number.
+    #[default]
+    Strict,
+    /// Count the line as 0.
+    Skip,
+    /// Count the line as the given value.
+    Default(usize),
+}
+
+/// A calibration sum together with the lines that had no digit and were
+/// counted according to the [`MissingDigitPolicy`].
+#[derive(Debug, Default)]
+pub struct Calibration {
+    pub sum: usize,
+    pub warnings: Vec<CalibrationError>,
+}
+
+#[derive(Debug)]
+pub enum CalibrationError {
+    MissingDigit {
+        line: usize,
+        column: usize,
+        text: String,
+    },
+    Io {
+        line: usize,
+        error: io::Error,
+    },
+    /// An [`Extraction`](extractor::Extraction) position the line has no
+    /// digit for.
+    PositionOutOfRange {
+        line: usize,
+        position: isize,
+        count: usize,
+        text: String,
+    },
+    /// An [`Extraction`](extractor::Extraction) that selects no digit.
+    EmptyExtraction,
+}
+
+impl CalibrationError {
+    fn missing_digit(index: usize, text: &str) -> CalibrationError {
+        CalibrationError::MissingDigit {
+            line: index + 1,
+            column: 1,
+            text: text.to_string(),
+        }
+    }
+}
+
+impl Diagnose for CalibrationError {
+    fn diagnostic(&self) -> Diagnostic {
+        match self {
+            CalibrationError::MissingDigit { line, column, text } => {
+                Diagnostic::new("no digit found")
+                    .at(*line, *column, text.chars().count(), text)
+                    .with_hint("every calibration line needs at least one digit")
+            }
+            CalibrationError::Io { line, error } => {
+                Diagnostic::new(format!("cannot read line {}: {}", line, error))
+            }
+            CalibrationError::PositionOutOfRange {
+                line,
+                position,
+                count,
+                text,
+            } => Diagnostic::new(format!("no digit at position {}", position))
+                .at(*line, 1, text.chars().count(), text)
+                .with_hint(format!("the line has {} digits", count)),
+            CalibrationError::EmptyExtraction => {
+                Diagnostic::new("the extraction selects no digits")
+                    .with_hint("use a positive count or at least one position")
+            }
+        }
+    }
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.diagnostic())
+    }
+}
+
+impl std::error::Error for CalibrationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CalibrationError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Sums `extract` over `lines`, applying `policy` to lines where it finds
+/// nothing.
+fn calibrate<L: AsRef<str> + Sync>(
+    lines: &[L],
+    policy: MissingDigitPolicy,
+    extract: impl Fn(&str) -> Option<usize> + Sync,
+) -> Result<Calibration, CalibrationError> {
+    #[cfg(not(feature = "parallel"))]
+    let values = lines.iter().map(|line| extract(line.as_ref()));
+    // Lines are extracted in parallel, the policy is still applied in line
+    // order so errors and warnings match the sequential path.
+    #[cfg(feature = "parallel")]
+    let values = {
+        use rayon::prelude::*;
+        let values: Vec<Option<usize>> = lines
+            .par_iter()
+            .map(|line| extract(line.as_ref()))
+            .collect();
+        values.into_iter()
+    };
+
+    let mut calibration = Calibration::default();
+    for ((index, line), value) in lines.iter().enumerate().zip(values) {
+        let line = line.as_ref();
+        calibration.sum += match (value, policy) {
+            (Some(value), _) => value,
+            (None, MissingDigitPolicy::Strict) => {
+                return Err(CalibrationError::missing_digit(index, line))
+            }
+            (None, MissingDigitPolicy::Skip) => {
+                calibration
+                    .warnings
+                    .push(CalibrationError::missing_digit(index, line));
+                0
+            }
+            (None, MissingDigitPolicy::Default(value)) => {
+                calibration
+                    .warnings
+                    .push(CalibrationError::missing_digit(index, line));
+                value
+            }
+        };
+    }
+    Ok(calibration)
+}
+
+/// Calls `f` with the index and text of every line of `reader`. One buffer
+/// is reused, so memory stays bounded by the longest line.
+fn for_each_line<R: BufRead>(
+    mut reader: R,
+    mut f: impl FnMut(usize, &str) -> Result<(), CalibrationError>,
+) -> Result<(), CalibrationError> {
+    let mut buffer = String::new();
+    for index in 0.. {
+        buffer.clear();
+        let read = reader
+            .read_line(&mut buffer)
+            .map_err(|error| CalibrationError::Io {
+                line: index + 1,
+                error,
+            })?;
+        if read == 0 {
+            break;
+        }
+        let line = buffer.strip_suffix('\n').unwrap_or(&buffer);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        f(index, line)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solution_parses_lines_once() {
+        let lines = Day1::parse("1abc2\ntwo1nine\r\nzoneight234\n").unwrap();
+
+        assert_eq!(lines, ["1abc2", "two1nine", "zoneight234"]);
+        assert_eq!(Day1::part_one(&lines).unwrap(), 12 + 11 + 24);
+        assert_eq!(Day1::part_two(&lines).unwrap(), 12 + 29 + 14);
+    }
+}
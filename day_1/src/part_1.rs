@@ -1,23 +1,78 @@
-use std::fmt::Error;
+use crate::trace::LineTrace;
+use crate::{Calibration, CalibrationError, DigitMatch, MissingDigitPolicy, Numerals};
+use std::io::BufRead;
 
-pub fn process(input: &str) -> Result<usize, Error> {
-    let result = input
+pub fn process(input: &str) -> Result<usize, CalibrationError> {
+    process_with(input, Numerals::Ascii)
+}
+
+/// Same as [`process`], recognising `numerals` as digits.
+pub fn process_with(input: &str, numerals: Numerals) -> Result<usize, CalibrationError> {
+    process_lenient(input, numerals, MissingDigitPolicy::Strict).map(|calibration| calibration.sum)
+}
+
+/// Same as [`process_with`], counting lines without a digit according to
+/// `policy` instead of failing.
+pub fn process_lenient(
+    input: &str,
+    numerals: Numerals,
+    policy: MissingDigitPolicy,
+) -> Result<Calibration, CalibrationError> {
+    let lines: Vec<&str> = input.lines().collect();
+    crate::calibrate(&lines, policy, |line| extract_numbers(line, numerals))
+}
+
+/// Same as [`process`], over a document already split into lines.
+pub fn process_lines<L: AsRef<str> + Sync>(lines: &[L]) -> Result<usize, CalibrationError> {
+    crate::calibrate(lines, MissingDigitPolicy::Strict, |line| {
+        extract_numbers(line, Numerals::Ascii)
+    })
+    .map(|calibration| calibration.sum)
+}
+
+/// Same as [`process`], reading the document line by line.
+pub fn process_reader<R: BufRead>(reader: R) -> Result<usize, CalibrationError> {
+    process_reader_with(reader, Numerals::Ascii)
+}
+
+/// Same as [`process_with`], reading the document line by line.
+pub fn process_reader_with<R: BufRead>(
+    reader: R,
+    numerals: Numerals,
+) -> Result<usize, CalibrationError> {
+    let mut sum = 0;
+    crate::for_each_line(reader, |index, line| {
+        sum += extract_numbers(line, numerals)
+            .ok_or_else(|| CalibrationError::missing_digit(index, line))?;
+        Ok(())
+    })?;
+    Ok(sum)
+}
+
+/// First and last digit of every line, see [`LineTrace`].
+pub fn trace(input: &str, numerals: Numerals) -> Vec<LineTrace> {
+    input
         .lines()
-        .map(|line| extract_numbers(line).unwrap())
-        .sum();
+        .enumerate()
+        .map(|(index, line)| LineTrace::new(index, line, digits(line, numerals)))
+        .collect()
+}
 
-    Ok(result)
+pub(crate) fn digits(line: &str, numerals: Numerals) -> impl Iterator<Item = DigitMatch> + '_ {
+    line.char_indices().filter_map(move |(start, ch)| {
+        Some(DigitMatch {
+            start,
+            end: start + ch.len_utf8(),
+            digit: numerals.value(ch)?,
+        })
+    })
 }
 
-fn extract_numbers(line: &str) -> Result<usize, Error> {
-    let numbers = line
-        .chars()
-        .filter(|ch| ch.is_ascii_digit())
-        .map(|ch| ch as u8 - b'0')
-        .collect::<Vec<_>>();
-    let first = *numbers.first().unwrap();
-    let last = *numbers.last().unwrap();
-    Ok((10 * (first as u16) + (last as u16)).into())
+fn extract_numbers(line: &str, numerals: Numerals) -> Option<usize> {
+    let numbers = digits(line, numerals).map(|m| m.digit).collect::<Vec<_>>();
+    let first = *numbers.first()?;
+    let last = *numbers.last()?;
+    Some((10 * (first as u16) + (last as u16)).into())
 }
 
 #[cfg(test)]
@@ -31,9 +86,55 @@ mod tests {
     #[case::three_numbers_no_text("132", 12)]
     #[case::three_numbers_with_text("13ab23nain2", 12)]
     fn test_extracting_numbers(#[case] input: &str, #[case] expected: usize) {
-        let result = extract_numbers(input);
-        assert!(result.is_ok());
-        assert_eq!(result, Ok(expected));
+        let result = extract_numbers(input, Numerals::Ascii);
+        assert!(result.is_some());
+        assert_eq!(result, Some(expected));
+    }
+
+    #[rstest]
+    #[case::full_width("ab１２３", 13)]
+    #[case::arabic_indic("x٤y٧z", 47)]
+    #[case::mixed_scripts("٣a7b５", 35)]
+    #[case::ascii_only("1abc2", 12)]
+    fn test_extracting_unicode_numbers(#[case] input: &str, #[case] expected: usize) {
+        assert_eq!(extract_numbers(input, Numerals::Unicode), Some(expected));
+    }
+
+    #[test]
+    fn test_unicode_numerals_are_ignored_by_default() {
+        assert_eq!(extract_numbers("٣a7b５", Numerals::Ascii), Some(77));
+        assert!(process("１２３").is_err());
+        assert_eq!(process_with("１２３\n٤x٧", Numerals::Unicode).unwrap(), 60);
+    }
+
+    #[rstest]
+    #[case::skip(MissingDigitPolicy::Skip, 12 + 38)]
+    #[case::default(MissingDigitPolicy::Default(50), 12 + 50 + 38 + 50)]
+    fn test_lenient_policies(#[case] policy: MissingDigitPolicy, #[case] expected: usize) {
+        let input = "1abc2\nnothing\npqr3stu8vwx\n";
+        let calibration =
+            process_lenient(&format!("{}again", input), Numerals::Ascii, policy).unwrap();
+
+        assert_eq!(calibration.sum, expected);
+        let lines: Vec<_> = calibration
+            .warnings
+            .iter()
+            .map(|warning| match warning {
+                CalibrationError::MissingDigit { line, .. } => *line,
+                other => panic!("unexpected warning {:?}", other),
+            })
+            .collect();
+        assert_eq!(lines, [2, 4]);
+    }
+
+    #[test]
+    fn test_strict_policy_fails_on_first_missing_digit() {
+        let result = process_lenient("1\nnone\n2", Numerals::Ascii, MissingDigitPolicy::Strict);
+
+        assert!(matches!(
+            result,
+            Err(CalibrationError::MissingDigit { line: 2, .. })
+        ));
     }
 
     #[test]
@@ -42,6 +143,48 @@ mod tests {
 pqr3stu8vwx
 a1b2c3d4e5f
 treb7uchet";
-        assert_eq!(process(input), Ok(142));
+        assert_eq!(process(input).unwrap(), 142);
+    }
+
+    #[test]
+    fn test_sample_input_from_reader() {
+        let input = "1abc2\r\npqr3stu8vwx\r\na1b2c3d4e5f\r\ntreb7uchet\n";
+        assert_eq!(process_reader(input.as_bytes()).unwrap(), 142);
+    }
+
+    #[test]
+    fn test_reader_reports_line_without_digit() {
+        let result = process_reader("1abc2\npqrstuvwx\n".as_bytes());
+
+        assert!(matches!(
+            result,
+            Err(CalibrationError::MissingDigit { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_reader_reports_invalid_utf8() {
+        let input: &[u8] = b"1abc2\n\xff3\n";
+
+        assert!(matches!(
+            process_reader(input),
+            Err(CalibrationError::Io { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_line_without_digit_is_reported() {
+        let input = "1abc2
+pqrstuvwx";
+        let result = process(input);
+
+        match result {
+            Err(CalibrationError::MissingDigit { line, column, text }) => {
+                assert_eq!(line, 2);
+                assert_eq!(column, 1);
+                assert_eq!(text, "pqrstuvwx");
+            }
+            other => panic!("expected MissingDigit, got {:?}", other),
+        }
     }
 }
@@ -1,38 +1,69 @@
-use aho_corasick::AhoCorasick;
-use std::fmt::Error;
+use crate::trace::LineTrace;
+use crate::vocabulary::DigitVocabulary;
+use crate::{Calibration, CalibrationError, MissingDigitPolicy};
+use std::io::BufRead;
 
-pub fn process(input: &str) -> Result<usize, Error> {
-    let aho = prepare_aho().unwrap();
-    let result: usize = input
-        .lines()
-        .map(|line| extract_numbers(line, &aho).unwrap())
-        .sum();
-    Ok(result)
+pub fn process(input: &str) -> Result<usize, CalibrationError> {
+    process_with(input, &DigitVocabulary::english())
 }
 
-fn prepare_aho() -> Result<AhoCorasick, Error> {
-    let keywords = vec![
-        "one", "1", "two", "2", "three", "3", "four", "4", "five", "5", "six", "6", "seven", "7",
-        "eight", "8", "nine", "9",
-    ];
+/// Same as [`process`], recognising the words of `vocabulary`.
+pub fn process_with(input: &str, vocabulary: &DigitVocabulary) -> Result<usize, CalibrationError> {
+    process_lenient(input, vocabulary, MissingDigitPolicy::Strict)
+        .map(|calibration| calibration.sum)
+}
 
-    Ok(AhoCorasick::new(keywords).unwrap())
+/// Same as [`process_with`], counting lines without a digit according to
+/// `policy` instead of failing.
+pub fn process_lenient(
+    input: &str,
+    vocabulary: &DigitVocabulary,
+    policy: MissingDigitPolicy,
+) -> Result<Calibration, CalibrationError> {
+    let lines: Vec<&str> = input.lines().collect();
+    crate::calibrate(&lines, policy, |line| extract_numbers(line, vocabulary))
 }
 
-fn extract_numbers(line: &str, pattern_matcher: &AhoCorasick) -> Result<usize, Error> {
-    let mut numbers = pattern_matcher
-        .find_overlapping_iter(line)
-        .map(|m| m.pattern());
-    let first = (numbers.next().unwrap().as_usize() / 2) + 1;
-    // PaternID is the index of the keyword in the keywords vector.
-    // for each digit we have 2 consecutive indexes, e.g - indexes 2, 3 -> 2
-    if let Some(last_pattern) = numbers.last() {
-        let last = last_pattern.as_usize() / 2 + 1;
-
-        Ok(10 * first + last)
-    } else {
-        Ok(10 * first + first)
-    }
+/// Same as [`process`], over a document already split into lines.
+pub fn process_lines<L: AsRef<str> + Sync>(lines: &[L]) -> Result<usize, CalibrationError> {
+    let vocabulary = DigitVocabulary::english();
+    crate::calibrate(lines, MissingDigitPolicy::Strict, |line| {
+        extract_numbers(line, &vocabulary)
+    })
+    .map(|calibration| calibration.sum)
+}
+
+/// Same as [`process`], reading the document line by line.
+pub fn process_reader<R: BufRead>(reader: R) -> Result<usize, CalibrationError> {
+    process_reader_with(reader, &DigitVocabulary::english())
+}
+
+/// Same as [`process_with`], reading the document line by line.
+pub fn process_reader_with<R: BufRead>(
+    reader: R,
+    vocabulary: &DigitVocabulary,
+) -> Result<usize, CalibrationError> {
+    let mut sum = 0;
+    crate::for_each_line(reader, |index, line| {
+        sum += extract_numbers(line, vocabulary)
+            .ok_or_else(|| CalibrationError::missing_digit(index, line))?;
+        Ok(())
+    })?;
+    Ok(sum)
+}
+
+/// First and last digit or word of every line, see [`LineTrace`].
+pub fn trace(input: &str, vocabulary: &DigitVocabulary) -> Vec<LineTrace> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| LineTrace::new(index, line, vocabulary.matches(line)))
+        .collect()
+}
+
+fn extract_numbers(line: &str, vocabulary: &DigitVocabulary) -> Option<usize> {
+    let (first, last) = vocabulary.first_and_last(line)?;
+    Some(10 * first.digit as usize + last.digit as usize)
 }
 
 #[cfg(test)]
@@ -42,8 +73,8 @@ mod tests {
 
     #[fixture]
     #[once]
-    fn make_aho() -> AhoCorasick {
-        prepare_aho().unwrap()
+    fn english() -> DigitVocabulary {
+        DigitVocabulary::english()
     }
 
     #[rstest]
@@ -51,7 +82,7 @@ mod tests {
     #[case::only_numbers("twonine", 29)]
     #[case::one_number("znfqfjcspf8md", 88)]
     fn test_extracting_numbers(#[case] input: &str, #[case] expected: usize) {
-        let result = extract_numbers(input, &make_aho()).unwrap();
+        let result = extract_numbers(input, &english()).unwrap();
 
         assert_eq!(result, expected);
     }
@@ -66,6 +97,96 @@ mod tests {
         zoneight234
         7pqrstsixteen";
 
-        assert_eq!(process(input), Ok(281))
+        assert_eq!(process(input).unwrap(), 281)
+    }
+
+    #[test]
+    fn sample_input_from_reader() {
+        let input = "two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+";
+
+        assert_eq!(process_reader(input.as_bytes()).unwrap(), 281)
+    }
+
+    #[rstest]
+    #[case::zero("zero4", 4)]
+    #[case::abbreviation("fivzero", 50)]
+    #[case::other_language("deuxtrois", 23)]
+    fn custom_vocabulary(#[case] input: &str, #[case] expected: usize) {
+        let vocabulary =
+            DigitVocabulary::new([("zero", 0), ("fiv", 5), ("deux", 2), ("trois", 3)]).unwrap();
+
+        assert_eq!(extract_numbers(input, &vocabulary), Some(expected));
+    }
+
+    #[test]
+    fn word_inside_another_word() {
+        let vocabulary = DigitVocabulary::new([("abcd", 1), ("bc", 2)]).unwrap();
+
+        assert_eq!(process_with("abcd", &vocabulary).unwrap(), 12);
+        assert_eq!(process_with("abcdx", &vocabulary).unwrap(), 12);
+    }
+
+    #[rstest]
+    #[case::upper_case_words("ONEabcTWO", 12)]
+    #[case::mixed_scripts("Seven٣xNINE", 79)]
+    #[case::full_width_only("ａ５ｂ", 55)]
+    fn unicode_english(#[case] input: &str, #[case] expected: usize) {
+        let vocabulary = DigitVocabulary::english().unicode().unwrap();
+
+        assert_eq!(extract_numbers(input, &vocabulary), Some(expected));
+    }
+
+    #[test]
+    fn unicode_accented_words() {
+        let vocabulary = DigitVocabulary::new([("čtyři", 4), ("pět", 5), ("tři", 3)])
+            .unwrap()
+            .unicode()
+            .unwrap();
+
+        assert_eq!(
+            process_with("ČTYŘIxxPĚT\nTři٧", &vocabulary).unwrap(),
+            45 + 37
+        );
+        assert!(process_with("ČTYŘI", &DigitVocabulary::english()).is_err());
+    }
+
+    #[test]
+    fn lenient_sum_keeps_dropped_lines() {
+        let calibration = process_lenient(
+            "two1nine\nabcxyz\nfour",
+            &english(),
+            MissingDigitPolicy::Skip,
+        )
+        .unwrap();
+
+        assert_eq!(calibration.sum, 29 + 44);
+        assert_eq!(calibration.warnings.len(), 1);
+        assert!(calibration.warnings[0].to_string().contains("abcxyz"));
+    }
+
+    #[test]
+    fn line_without_digit_is_reported() {
+        let result = process("two1nine\nabcxyz");
+
+        assert!(matches!(
+            result,
+            Err(CalibrationError::MissingDigit { line: 2, .. })
+        ));
+    }
+    #[test]
+    fn zero_is_not_a_digit() {
+        let result = process("0abc");
+
+        assert!(matches!(
+            result,
+            Err(CalibrationError::MissingDigit { line: 1, .. })
+        ));
     }
 }
@@ -0,0 +1,186 @@
+use crate::DigitMatch;
+use serde_json::{json, Value};
+use std::fmt::Write;
+
+/// A digit or digit word as it appears in the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found {
+    /// 1-based character column.
+    pub column: usize,
+    pub text: String,
+    pub digit: u8,
+}
+
+impl Found {
+    fn new(line: &str, found: DigitMatch) -> Found {
+        Found {
+            column: line[..found.start].chars().count() + 1,
+            text: line[found.start..found.end].to_string(),
+            digit: found.digit,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "column": self.column,
+            "text": self.text,
+            "digit": self.digit,
+        })
+    }
+}
+
+/// What a single line contributes to the calibration sum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineTrace {
+    /// 1-based line number.
+    pub line: usize,
+    pub first: Option<Found>,
+    pub last: Option<Found>,
+}
+
+impl LineTrace {
+    pub(crate) fn new(
+        index: usize,
+        line: &str,
+        mut matches: impl Iterator<Item = DigitMatch>,
+    ) -> LineTrace {
+        let first = matches.next();
+        let last = matches.last().or(first);
+        LineTrace {
+            line: index + 1,
+            first: first.map(|found| Found::new(line, found)),
+            last: last.map(|found| Found::new(line, found)),
+        }
+    }
+
+    /// The two-digit calibration value, `None` when the line has no digit.
+    pub fn value(&self) -> Option<usize> {
+        let first = self.first.as_ref()?.digit as usize;
+        let last = self.last.as_ref()?.digit as usize;
+        Some(10 * first + last)
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "line": self.line,
+            "first": self.first.as_ref().map(Found::to_json),
+            "last": self.last.as_ref().map(Found::to_json),
+            "value": self.value(),
+        })
+    }
+}
+
+/// Renders `traces` as an aligned table, lines without a digit marked `-`.
+pub fn table(traces: &[LineTrace]) -> String {
+    let found = |found: &Option<Found>| match found {
+        Some(found) => format!("{}@{}", found.text, found.column),
+        None => "-".to_string(),
+    };
+    let rows: Vec<[String; 4]> = traces
+        .iter()
+        .map(|trace| {
+            [
+                trace.line.to_string(),
+                found(&trace.first),
+                found(&trace.last),
+                trace
+                    .value()
+                    .map_or_else(|| "-".to_string(), |value| value.to_string()),
+            ]
+        })
+        .collect();
+
+    let header = ["line", "first", "last", "value"];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    write_row(&mut table, &header, &widths);
+    for row in &rows {
+        write_row(&mut table, row, &widths);
+    }
+    table
+}
+
+/// Numbers are right-aligned, matches left-aligned.
+fn write_row<S: AsRef<str>>(table: &mut String, cells: &[S; 4], widths: &[usize; 4]) {
+    let [line, first, last, value] = cells.each_ref().map(AsRef::as_ref);
+    let _ = writeln!(
+        table,
+        "{:>w0$}  {:<w1$}  {:<w2$}  {:>w3$}",
+        line,
+        first,
+        last,
+        value,
+        w0 = widths[0],
+        w1 = widths[1],
+        w2 = widths[2],
+        w3 = widths[3],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vocabulary::DigitVocabulary;
+    use crate::{part_1, part_2, Numerals};
+
+    #[test]
+    fn test_trace_reports_first_and_last_word() {
+        let traces = part_2::trace("xtwone3four\nabc", &DigitVocabulary::english());
+
+        assert_eq!(
+            traces[0].first,
+            Some(Found {
+                column: 2,
+                text: "two".to_string(),
+                digit: 2
+            })
+        );
+        assert_eq!(
+            traces[0].last,
+            Some(Found {
+                column: 8,
+                text: "four".to_string(),
+                digit: 4
+            })
+        );
+        assert_eq!(traces[0].value(), Some(24));
+        assert_eq!(traces[1].value(), None);
+    }
+
+    #[test]
+    fn test_single_digit_is_first_and_last() {
+        let traces = part_1::trace("treb7uchet", Numerals::Ascii);
+
+        assert_eq!(traces[0].first, traces[0].last);
+        assert_eq!(traces[0].value(), Some(77));
+    }
+
+    #[test]
+    fn test_trace_values_sum_to_answer() {
+        let input = "two1nine\neightwothree\nabcone2threexyz\nxtwone3four";
+        let traces = part_2::trace(input, &DigitVocabulary::english());
+
+        let sum: usize = traces.iter().filter_map(LineTrace::value).sum();
+        assert_eq!(sum, part_2::process(input).unwrap());
+    }
+
+    #[test]
+    fn test_table_and_json() {
+        let traces = part_1::trace("a1b2\nnone", Numerals::Ascii);
+
+        assert_eq!(
+            table(&traces),
+            "line  first  last  value\n   1  1@2    2@4      12\n   2  -      -         -\n"
+        );
+        assert_eq!(
+            traces[1].to_json().to_string(),
+            r#"{"first":null,"last":null,"line":2,"value":null}"#
+        );
+    }
+}
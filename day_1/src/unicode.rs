@@ -0,0 +1,86 @@
+/// Code points of the digit zero of every run of Unicode decimal digits
+/// (general category `Nd`); each run holds the digits 0-9 in order.
+const DIGIT_ZEROS: [u32; 76] = [
+    0x0030, 0x0660, 0x06F0, 0x07C0, 0x0966, 0x09E6, 0x0A66, 0x0AE6, 0x0B66, 0x0BE6, 0x0C66, 0x0CE6,
+    0x0D66, 0x0DE6, 0x0E50, 0x0ED0, 0x0F20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80,
+    0x1A90, 0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0,
+    0xFF10, 0x104A0, 0x10D30, 0x10D40, 0x11066, 0x110F0, 0x11136, 0x111D0, 0x112F0, 0x11450,
+    0x114D0, 0x11650, 0x116C0, 0x116D0, 0x116DA, 0x11730, 0x118E0, 0x11950, 0x11BF0, 0x11C50,
+    0x11D50, 0x11DA0, 0x11F50, 0x16130, 0x16A60, 0x16AC0, 0x16B50, 0x16D70, 0x1CCF0, 0x1D7CE,
+    0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140, 0x1E2F0, 0x1E4F0, 0x1E5F1, 0x1E950, 0x1FBF0,
+];
+
+/// Value of a decimal digit from any script, e.g. `'٣'` or `'３'`.
+pub fn decimal_value(ch: char) -> Option<u8> {
+    let code = ch as u32;
+    let run = match DIGIT_ZEROS.binary_search(&code) {
+        Ok(index) => index,
+        Err(0) => return None,
+        Err(index) => index - 1,
+    };
+    let value = code - DIGIT_ZEROS[run];
+    (value < 10).then_some(value as u8)
+}
+
+/// `line` in lowercase, remembering where every byte came from.
+pub(crate) struct Folded {
+    pub(crate) text: String,
+    offsets: Vec<usize>,
+}
+
+impl Folded {
+    pub(crate) fn new(line: &str) -> Folded {
+        let mut text = String::with_capacity(line.len());
+        let mut offsets = Vec::with_capacity(line.len() + 1);
+        for (offset, ch) in line.char_indices() {
+            text.extend(ch.to_lowercase());
+            offsets.resize(text.len(), offset);
+        }
+        offsets.push(line.len());
+        Folded { text, offsets }
+    }
+
+    /// Byte offset in the original line of byte `offset` of the folded text.
+    pub(crate) fn original(&self, offset: usize) -> usize {
+        self.offsets[offset]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case::ascii('7', Some(7))]
+    #[case::full_width('３', Some(3))]
+    #[case::arabic_indic('٩', Some(9))]
+    #[case::devanagari('०', Some(0))]
+    #[case::mathematical_bold('𝟐', Some(2))]
+    #[case::letter('a', None)]
+    #[case::vulgar_fraction('½', None)]
+    #[case::roman_numeral('Ⅳ', None)]
+    fn test_decimal_value(#[case] ch: char, #[case] expected: Option<u8>) {
+        assert_eq!(decimal_value(ch), expected);
+    }
+
+    #[test]
+    fn test_every_run_is_numeric() {
+        assert!(DIGIT_ZEROS.windows(2).all(|pair| pair[1] - pair[0] >= 10));
+        for zero in DIGIT_ZEROS {
+            for value in 0..10 {
+                let ch = char::from_u32(zero + value).unwrap();
+                assert!(ch.is_numeric(), "{:?} is not numeric", ch);
+            }
+        }
+    }
+
+    #[test]
+    fn test_folded_offsets_point_into_original() {
+        let folded = Folded::new("ÉinS");
+
+        assert_eq!(folded.text, "éins");
+        assert_eq!(folded.original(2), 2);
+        assert_eq!(folded.original(folded.text.len()), "ÉinS".len());
+    }
+}
@@ -0,0 +1,493 @@
+use crate::unicode::{decimal_value, Folded};
+use crate::{DigitMatch, Numerals};
+use aho_corasick::{AhoCorasick, BuildError, PatternID};
+use aoc_common::diagnostic::{Diagnose, Diagnostic};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::{fmt, io};
+
+const NUMERALS: [&str; 9] = ["1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+const ENGLISH: [(&str, u8); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+/// Words spelling out digits, recognised next to the numerals `1`-`9`.
+///
+/// Zero is not a digit of the puzzle: it is only recognised as a word the
+/// vocabulary maps to 0, and the numeral itself only when the vocabulary
+/// holds `0 = 0`, which in [`Numerals::Unicode`] mode also enables the zeros
+/// of other scripts.
+///
+/// A vocabulary file holds one `word = digit` mapping per line; blank lines
+/// and lines starting with `#` are ignored.
+#[derive(Debug, Clone)]
+pub struct DigitVocabulary {
+    words: Vec<String>,
+    digits: Vec<u8>,
+    numerals: Numerals,
+    /// Whether the numeral `0` was asked for.
+    zero: bool,
+    matcher: AhoCorasick,
+    /// Length in bytes of the longest word.
+    longest: usize,
+}
+
+impl DigitVocabulary {
+    pub fn new<W: AsRef<str>>(
+        entries: impl IntoIterator<Item = (W, u8)>,
+    ) -> Result<DigitVocabulary, VocabularyError> {
+        DigitVocabulary::with_numerals(entries, Numerals::Ascii)
+    }
+
+    /// With [`Numerals::Unicode`] words match regardless of case and
+    /// decimal digits of every script are recognised.
+    pub fn with_numerals<W: AsRef<str>>(
+        entries: impl IntoIterator<Item = (W, u8)>,
+        numerals: Numerals,
+    ) -> Result<DigitVocabulary, VocabularyError> {
+        let mut words: Vec<String> = NUMERALS.iter().map(|numeral| numeral.to_string()).collect();
+        let mut digits: Vec<u8> = (1..=9).collect();
+
+        for (word, digit) in entries {
+            let word = word.as_ref();
+            if word.is_empty() {
+                return Err(VocabularyError::EmptyWord { line: None });
+            }
+            if digit > 9 {
+                return Err(VocabularyError::InvalidDigit {
+                    line: None,
+                    text: digit.to_string(),
+                });
+            }
+            match numerals {
+                Numerals::Ascii => words.push(word.to_string()),
+                Numerals::Unicode => words.push(word.to_lowercase()),
+            }
+            digits.push(digit);
+        }
+
+        let zero = words
+            .iter()
+            .zip(&digits)
+            .any(|(word, digit)| word == "0" && *digit == 0);
+        let matcher = AhoCorasick::new(&words).map_err(VocabularyError::Matcher)?;
+        let longest = words.iter().map(String::len).max().unwrap_or(1);
+        Ok(DigitVocabulary {
+            words,
+            digits,
+            numerals,
+            zero,
+            matcher,
+            longest,
+        })
+    }
+
+    /// The same words in [`Numerals::Unicode`] mode.
+    pub fn unicode(&self) -> Result<DigitVocabulary, VocabularyError> {
+        let entries = self.words().skip(NUMERALS.len());
+        DigitVocabulary::with_numerals(entries, Numerals::Unicode)
+    }
+
+    /// "one" to "nine", the words of the original puzzle.
+    pub fn english() -> DigitVocabulary {
+        DigitVocabulary::new(ENGLISH).expect("the English vocabulary is valid")
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<DigitVocabulary, VocabularyError> {
+        fs::read_to_string(path)
+            .map_err(VocabularyError::Io)?
+            .parse()
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = (&str, u8)> {
+        self.words
+            .iter()
+            .map(String::as_str)
+            .zip(self.digits.iter().copied())
+    }
+
+    /// Every digit and word in `line`, overlapping ones included, ordered
+    /// by where they start, then by where they end.
+    pub fn matches<'a>(&'a self, line: &'a str) -> Box<dyn Iterator<Item = DigitMatch> + 'a> {
+        let mut matches = match self.numerals {
+            Numerals::Ascii => self.ascii_matches(line.as_bytes(), 0).collect(),
+            Numerals::Unicode => self.unicode_matches(line),
+        };
+        matches.sort_by_key(|m| (m.start, m.end));
+        Box::new(matches.into_iter())
+    }
+
+    /// The first and last of [`matches`](Self::matches), without scanning
+    /// the middle of long lines: the first match is known once a match ends
+    /// `longest` bytes after its start, and the last one is searched for in
+    /// a window at the end of the line that doubles until it holds a match.
+    pub fn first_and_last(&self, line: &str) -> Option<(DigitMatch, DigitMatch)> {
+        if self.numerals == Numerals::Unicode {
+            let mut matches = self.matches(line);
+            let first = matches.next()?;
+            return Some((first, matches.last().unwrap_or(first)));
+        }
+
+        // Matches come ordered by where they end, and one starting before
+        // `first` would have ended before `first.start + longest`.
+        let mut first: Option<DigitMatch> = None;
+        for m in self.ascii_matches(line.as_bytes(), 0) {
+            match first {
+                Some(best) if m.end >= best.start + self.longest => break,
+                Some(best) if (best.start, best.end) <= (m.start, m.end) => {}
+                _ => first = Some(m),
+            }
+        }
+        let first = first?;
+
+        let mut window = 2 * self.longest;
+        loop {
+            let start = line.len().saturating_sub(window);
+            // Every match starting inside the window lies inside it, so the
+            // last match of the window is the last one of the line.
+            let last = self
+                .ascii_matches(&line.as_bytes()[start..], start)
+                .max_by_key(|m| (m.start, m.end));
+            match last {
+                Some(last) => return Some((first, last)),
+                None if start == 0 => return Some((first, first)),
+                None => window *= 2,
+            }
+        }
+    }
+
+    /// Matches in `haystack`, which starts `offset` bytes into the line,
+    /// ordered by where they end.
+    fn ascii_matches<'a>(
+        &'a self,
+        haystack: &'a [u8],
+        offset: usize,
+    ) -> impl Iterator<Item = DigitMatch> + 'a {
+        self.matcher
+            .find_overlapping_iter(haystack)
+            .map(move |m| DigitMatch {
+                start: offset + m.start(),
+                end: offset + m.end(),
+                digit: self.digit(m.pattern()),
+            })
+    }
+
+    fn unicode_matches(&self, line: &str) -> Vec<DigitMatch> {
+        let folded = Folded::new(line);
+        let mut matches: Vec<DigitMatch> = self
+            .matcher
+            .find_overlapping_iter(&folded.text)
+            .map(|m| DigitMatch {
+                start: folded.original(m.start()),
+                end: folded.original(m.end()),
+                digit: self.digit(m.pattern()),
+            })
+            .collect();
+        matches.extend(
+            line.char_indices()
+                .filter(|(_, ch)| !ch.is_ascii())
+                .filter_map(|(start, ch)| {
+                    Some(DigitMatch {
+                        start,
+                        end: start + ch.len_utf8(),
+                        digit: decimal_value(ch).filter(|digit| *digit != 0 || self.zero)?,
+                    })
+                }),
+        );
+        matches
+    }
+
+    fn digit(&self, pattern: PatternID) -> u8 {
+        self.digits[pattern.as_usize()]
+    }
+}
+
+impl Default for DigitVocabulary {
+    fn default() -> DigitVocabulary {
+        DigitVocabulary::english()
+    }
+}
+
+impl FromStr for DigitVocabulary {
+    type Err = VocabularyError;
+
+    fn from_str(content: &str) -> Result<DigitVocabulary, VocabularyError> {
+        let mut entries = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let entry = line.trim();
+            if entry.is_empty() || entry.starts_with('#') {
+                continue;
+            }
+
+            let line = Some(index + 1);
+            let (word, digit) =
+                entry
+                    .split_once('=')
+                    .ok_or_else(|| VocabularyError::MalformedEntry {
+                        line: index + 1,
+                        text: entry.to_string(),
+                    })?;
+            let (word, digit) = (word.trim(), digit.trim());
+            if word.is_empty() {
+                return Err(VocabularyError::EmptyWord { line });
+            }
+            let digit = digit
+                .parse::<u8>()
+                .ok()
+                .filter(|digit| *digit <= 9)
+                .ok_or_else(|| VocabularyError::InvalidDigit {
+                    line,
+                    text: digit.to_string(),
+                })?;
+            entries.push((word, digit));
+        }
+
+        DigitVocabulary::new(entries)
+    }
+}
+
+#[derive(Debug)]
+pub enum VocabularyError {
+    MalformedEntry { line: usize, text: String },
+    EmptyWord { line: Option<usize> },
+    InvalidDigit { line: Option<usize>, text: String },
+    Matcher(BuildError),
+    Io(io::Error),
+}
+
+impl Diagnose for VocabularyError {
+    fn diagnostic(&self) -> Diagnostic {
+        let located = |message: String, line: &Option<usize>| match line {
+            Some(line) => Diagnostic::new(format!("line {}: {}", line, message)),
+            None => Diagnostic::new(message),
+        };
+        match self {
+            VocabularyError::MalformedEntry { line, text } => {
+                Diagnostic::new(format!("malformed vocabulary entry `{}`", text))
+                    .at(*line, 1, text.chars().count(), text)
+                    .with_hint("expected `<word> = <digit>`")
+            }
+            VocabularyError::EmptyWord { line } => {
+                located("a vocabulary word cannot be empty".to_string(), line)
+            }
+            VocabularyError::InvalidDigit { line, text } => {
+                located(format!("`{}` is not a digit between 0 and 9", text), line)
+            }
+            VocabularyError::Matcher(error) => {
+                Diagnostic::new(format!("cannot build digit matcher: {}", error))
+            }
+            VocabularyError::Io(error) => {
+                Diagnostic::new(format!("cannot read vocabulary: {}", error))
+            }
+        }
+    }
+}
+
+impl fmt::Display for VocabularyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.diagnostic())
+    }
+}
+
+impl std::error::Error for VocabularyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VocabularyError::Matcher(error) => Some(error),
+            VocabularyError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[test]
+    fn test_parse_vocabulary_file() {
+        let vocabulary: DigitVocabulary = "# French
+un = 1
+deux = 2
+
+zero = 0"
+            .parse()
+            .unwrap();
+
+        let words: Vec<_> = vocabulary.words().skip(NUMERALS.len()).collect();
+        assert_eq!(words, [("un", 1), ("deux", 2), ("zero", 0)]);
+    }
+
+    #[rstest]
+    #[case::english(DigitVocabulary::english(), "two1nine", Some(((2, 0), (9, 4))))]
+    #[case::no_match(DigitVocabulary::english(), "abcdefghijklmnopqrstuvwxyz", None)]
+    #[case::long_tail(
+        DigitVocabulary::english(),
+        &format!("xtwone{}eightwo", "x".repeat(100)),
+        Some(((2, 1), (2, 110)))
+    )]
+    #[case::far_apart(
+        DigitVocabulary::english(),
+        &format!("7{}", "z".repeat(1000)),
+        Some(((7, 0), (7, 0)))
+    )]
+    #[case::nested(nested(), "abcd", Some(((1, 0), (3, 2))))]
+    #[case::nested_tail(
+        nested(),
+        &format!("bc{}abcd", "-".repeat(33)),
+        Some(((2, 0), (3, 37)))
+    )]
+    #[case::unicode(
+        DigitVocabulary::english().unicode().unwrap(),
+        "ONE٣xx",
+        Some(((1, 0), (3, 3)))
+    )]
+    fn test_first_and_last(
+        #[case] vocabulary: DigitVocabulary,
+        #[case] line: &str,
+        #[case] expected: Option<((u8, usize), (u8, usize))>,
+    ) {
+        let found = vocabulary.first_and_last(line);
+        let digit_and_start = |m: DigitMatch| (m.digit, m.start);
+
+        assert_eq!(
+            found.map(|(first, last)| (digit_and_start(first), digit_and_start(last))),
+            expected
+        );
+        let matches: Vec<_> = vocabulary.matches(line).collect();
+        assert_eq!(
+            found,
+            matches.first().zip(matches.last()).map(|(f, l)| (*f, *l))
+        );
+    }
+
+    fn nested() -> DigitVocabulary {
+        DigitVocabulary::new([("abcd", 1), ("bc", 2), ("cd", 3), ("bcd", 4)]).unwrap()
+    }
+
+    #[test]
+    fn test_first_and_last_on_generated_lines() {
+        let vocabulary = nested();
+        let alphabet = ['a', 'b', 'c', 'd', '5', '-'];
+        let mut state = 7u64;
+        for length in 0..200 {
+            let line: String = (0..length)
+                .map(|_| {
+                    state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+                    alphabet[(state >> 33) as usize % alphabet.len()]
+                })
+                .collect();
+            let expected = naive_first_and_last(&vocabulary, &line);
+
+            assert_eq!(vocabulary.first_and_last(&line), expected, "{}", line);
+        }
+    }
+
+    /// Tries every word at every position of an ASCII `line`.
+    fn naive_first_and_last(
+        vocabulary: &DigitVocabulary,
+        line: &str,
+    ) -> Option<(DigitMatch, DigitMatch)> {
+        let matches: Vec<_> = (0..line.len())
+            .flat_map(|start| {
+                vocabulary
+                    .words()
+                    .filter(move |(word, _)| line[start..].starts_with(word))
+                    .map(move |(word, digit)| DigitMatch {
+                        start,
+                        end: start + word.len(),
+                        digit,
+                    })
+            })
+            .collect();
+        let first = matches.iter().min_by_key(|m| (m.start, m.end))?;
+        let last = matches.iter().max_by_key(|m| (m.start, m.end))?;
+        Some((*first, *last))
+    }
+
+    #[test]
+    fn test_unicode_matches_keep_original_offsets() {
+        let vocabulary = DigitVocabulary::new([("tři", 3), ("zwei", 2)])
+            .unwrap()
+            .unicode()
+            .unwrap();
+        let line = "ŽTŘIx٤ZWEI";
+
+        let matches: Vec<_> = vocabulary.matches(line).collect();
+        assert_eq!(
+            matches,
+            [
+                DigitMatch {
+                    start: 2,
+                    end: 6,
+                    digit: 3
+                },
+                DigitMatch {
+                    start: 7,
+                    end: 9,
+                    digit: 4
+                },
+                DigitMatch {
+                    start: 9,
+                    end: 13,
+                    digit: 2
+                },
+            ]
+        );
+        assert_eq!(&line[2..6], "TŘI");
+    }
+
+    #[rstest]
+    #[case::ascii(DigitVocabulary::english(), "0abc", None)]
+    #[case::arabic_indic(DigitVocabulary::english().unicode().unwrap(), "٠abc", None)]
+    #[case::word(DigitVocabulary::new([("zero", 0)]).unwrap(), "zero0", Some(0))]
+    #[case::opt_in(DigitVocabulary::new([("0", 0)]).unwrap(), "0abc", Some(0))]
+    #[case::opt_in_unicode(DigitVocabulary::new([("0", 0)]).unwrap().unicode().unwrap(), "٠abc", Some(0))]
+    fn test_zero_only_when_asked_for(
+        #[case] vocabulary: DigitVocabulary,
+        #[case] line: &str,
+        #[case] expected: Option<u8>,
+    ) {
+        assert_eq!(vocabulary.matches(line).next().map(|m| m.digit), expected);
+    }
+
+    #[test]
+    fn test_reject_digit_out_of_range() {
+        let result = "dix = 10".parse::<DigitVocabulary>();
+
+        assert!(matches!(
+            result,
+            Err(VocabularyError::InvalidDigit { line: Some(1), .. })
+        ));
+    }
+
+    #[test]
+    fn test_reject_entry_without_separator() {
+        let result = "un 1".parse::<DigitVocabulary>();
+
+        assert!(matches!(
+            result,
+            Err(VocabularyError::MalformedEntry { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_reject_empty_word_in_code() {
+        let result = DigitVocabulary::new([("", 1)]);
+
+        assert!(matches!(
+            result,
+            Err(VocabularyError::EmptyWord { line: None })
+        ));
+    }
+}
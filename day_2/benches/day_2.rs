@@ -0,0 +1,41 @@
+use aoc_common::bench::{bench_solution, Lcg};
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_2::Day2;
+
+const SAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+const COLOURS: [&str; 3] = ["red", "green", "blue"];
+
+fn generate(games: usize, seed: u64) -> String {
+    let mut rng = Lcg::new(seed);
+    let mut input = String::new();
+    for id in 1..=games {
+        let mut draws = Vec::new();
+        for draw in 0..1 + rng.below(6) {
+            let mut cubes = Vec::new();
+            for colour in COLOURS {
+                // The first draw shows every colour so each game has a power.
+                if draw == 0 || rng.below(3) > 0 {
+                    cubes.push(format!("{} {}", 1 + rng.below(20), colour));
+                }
+            }
+            if !cubes.is_empty() {
+                draws.push(cubes.join(", "));
+            }
+        }
+        input.push_str(&format!("Game {}: {}\n", id, draws.join("; ")));
+    }
+    input
+}
+
+fn benches(c: &mut Criterion) {
+    bench_solution::<Day2>(c, "sample", SAMPLE);
+    bench_solution::<Day2>(c, "large", &generate(100_000, 2));
+}
+
+criterion_group!(day_2, benches);
+criterion_main!(day_2);
@@ -0,0 +1,227 @@
+use crate::part_1::{is_colour_name, Game};
+use aoc_common::diagnostic::{Diagnose, Diagnostic};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::{fmt, io};
+
+/// How many cubes of each colour the bag holds.
+///
+/// Written as `colour = count` entries separated by commas or newlines;
+/// lines starting with `#` are ignored, so the same text works as a
+/// config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BagConfig {
+    limits: BTreeMap<String, usize>,
+}
+
+impl BagConfig {
+    pub fn new<C: Into<String>>(limits: impl IntoIterator<Item = (C, usize)>) -> BagConfig {
+        BagConfig {
+            limits: limits
+                .into_iter()
+                .map(|(colour, limit)| (colour.into(), limit))
+                .collect(),
+        }
+    }
+
+    /// 12 red, 13 green and 14 blue cubes, the bag of the original puzzle.
+    pub fn puzzle() -> BagConfig {
+        BagConfig::new([("red", 12), ("green", 13), ("blue", 14)])
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<BagConfig, BagConfigError> {
+        fs::read_to_string(path)
+            .map_err(BagConfigError::Io)?
+            .parse()
+    }
+
+    /// Cubes of `colour` in the bag; none when the colour is not listed.
+    pub fn limit(&self, colour: &str) -> usize {
+        self.limits.get(colour).copied().unwrap_or(0)
+    }
+
+    pub fn with_limit(mut self, colour: impl Into<String>, limit: usize) -> BagConfig {
+        self.limits.insert(colour.into(), limit);
+        self
+    }
+
+    /// This bag with the limits of `other` taking precedence.
+    pub fn merged(&self, other: &BagConfig) -> BagConfig {
+        let mut merged = self.clone();
+        merged.limits.extend(other.limits.clone());
+        merged
+    }
+
+    pub fn limits(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.limits
+            .iter()
+            .map(|(colour, limit)| (colour.as_str(), *limit))
+    }
+}
+
+impl Default for BagConfig {
+    fn default() -> BagConfig {
+        BagConfig::puzzle()
+    }
+}
+
+impl fmt::Display for BagConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let limits: Vec<_> = self
+            .limits()
+            .map(|(colour, limit)| format!("{}={}", colour, limit))
+            .collect();
+        write!(f, "{}", limits.join(", "))
+    }
+}
+
+impl FromStr for BagConfig {
+    type Err = BagConfigError;
+
+    fn from_str(content: &str) -> Result<BagConfig, BagConfigError> {
+        let mut limits = BTreeMap::new();
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            for entry in line.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                let (colour, limit) =
+                    entry
+                        .split_once('=')
+                        .ok_or_else(|| BagConfigError::MalformedEntry {
+                            line: line_number,
+                            text: entry.to_string(),
+                        })?;
+                let (colour, limit) = (colour.trim(), limit.trim());
+                if !is_colour_name(colour) {
+                    return Err(BagConfigError::UnknownColour {
+                        line: line_number,
+                        text: colour.to_string(),
+                    });
+                }
+                let limit = limit
+                    .parse::<usize>()
+                    .map_err(|_| BagConfigError::InvalidCount {
+                        line: line_number,
+                        text: limit.to_string(),
+                    })?;
+                limits.insert(colour.to_string(), limit);
+            }
+        }
+        Ok(BagConfig { limits })
+    }
+}
+
+/// Games that are impossible with `bag` but possible with `raised`, e.g.
+/// which games become valid if blue is raised to 20.
+pub fn newly_valid<'a>(
+    games: &'a [Game],
+    bag: &BagConfig,
+    raised: &BagConfig,
+) -> impl Iterator<Item = &'a Game> {
+    let (bag, raised) = (bag.clone(), raised.clone());
+    games
+        .iter()
+        .filter(move |game| !game.valid(&bag) && game.valid(&raised))
+}
+
+#[derive(Debug)]
+pub enum BagConfigError {
+    MalformedEntry { line: usize, text: String },
+    UnknownColour { line: usize, text: String },
+    InvalidCount { line: usize, text: String },
+    Io(io::Error),
+}
+
+impl Diagnose for BagConfigError {
+    fn diagnostic(&self) -> Diagnostic {
+        match self {
+            BagConfigError::MalformedEntry { line, text } => {
+                Diagnostic::new(format!("line {}: malformed bag entry `{}`", line, text))
+                    .with_hint("expected `<colour> = <count>`")
+            }
+            BagConfigError::UnknownColour { line, text } => {
+                Diagnostic::new(format!("line {}: unknown colour `{}`", line, text))
+                    .with_hint("colour names are single words, e.g. `blue`")
+            }
+            BagConfigError::InvalidCount { line, text } => {
+                Diagnostic::new(format!("line {}: invalid cube count `{}`", line, text))
+            }
+            BagConfigError::Io(error) => {
+                Diagnostic::new(format!("cannot read bag config: {}", error))
+            }
+        }
+    }
+}
+
+impl fmt::Display for BagConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.diagnostic())
+    }
+}
+
+impl std::error::Error for BagConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BagConfigError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[test]
+    fn test_parse_config() {
+        let bag: BagConfig = "# bigger bag\nred = 20, green=1\nblue = 3\n"
+            .parse()
+            .unwrap();
+
+        assert_eq!(bag.limit("red"), 20);
+        assert_eq!(bag.limit("green"), 1);
+        assert_eq!(bag.limit("blue"), 3);
+        assert_eq!(bag.to_string(), "blue=3, green=1, red=20");
+    }
+
+    #[rstest]
+    #[case::malformed("red 12")]
+    #[case::unknown_colour("bl ue = 3")]
+    #[case::invalid_count("red = -1")]
+    fn test_reject_invalid_config(#[case] config: &str) {
+        assert!(config.parse::<BagConfig>().is_err());
+    }
+
+    #[test]
+    fn test_missing_colour_holds_no_cubes() {
+        let bag = BagConfig::new([("red", 5)]);
+
+        assert_eq!(bag.limit("blue"), 0);
+    }
+
+    #[test]
+    fn test_games_valid_once_blue_is_raised() {
+        let games = crate::parse(
+            "Game 1: 3 blue, 4 red\n\
+             Game 2: 15 blue, 1 red\n\
+             Game 3: 20 blue\n\
+             Game 4: 15 blue, 20 red",
+        )
+        .unwrap()
+        .value;
+        let bag = BagConfig::puzzle();
+        let raised = bag.merged(&"blue = 15".parse().unwrap());
+
+        let ids: Vec<_> = newly_valid(&games, &bag, &raised)
+            .map(|game| game.id())
+            .collect();
+
+        assert_eq!(ids, [2]);
+    }
+}
@@ -1,18 +1,7 @@
-use day_2::part_1::process;
-use day_2::part_2::process as part2;
-fn main() {
-    let input = include_str!("../../input.txt");
+use aoc_common::report;
+use day_2::Day2;
+use std::process::ExitCode;
 
-    let result = process(input);
-
-    match result {
-        Ok(result) => println!("Result: {}", result),
-        Err(error) => println!("Error: {}", error),
-    }
-
-    let result2 = part2(input);
-    match result2 {
-        Ok(result) => println!("Result part 2: {}", result),
-        Err(error) => println!("Error: {}", error),
-    }
+fn main() -> ExitCode {
+    report::main::<Day2>(&[1, 2])
 }
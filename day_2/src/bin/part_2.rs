@@ -1,3 +1,7 @@
-fn main() {
-    println!("Hello, world!");
+use aoc_common::report;
+use day_2::Day2;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    report::main::<Day2>(&[2])
 }
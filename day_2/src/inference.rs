@@ -0,0 +1,273 @@
+//! Estimates the bag behind observed games.
+//!
+//! Every draw is a handful taken from the full bag without replacement, so
+//! its probability under a candidate bag is multivariate hypergeometric:
+//! `Π C(bag[c], shown[c]) / C(bag total, handful size)`. Candidates range
+//! from [`Game::min_draw`] up to `slack` extra cubes per colour, and their
+//! confidence is the share of the total likelihood, i.e. the posterior
+//! under a uniform prior over the candidates.
+
+use crate::bag::BagConfig;
+use crate::part_1::{Draw, Game};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InferenceOptions {
+    /// Cubes above the observed minimum considered for every colour.
+    pub slack: usize,
+    /// Number of candidates returned.
+    pub top: usize,
+}
+
+impl Default for InferenceOptions {
+    fn default() -> InferenceOptions {
+        InferenceOptions { slack: 10, top: 5 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub bag: BagConfig,
+    pub log_likelihood: f64,
+    /// Between 0 and 1; sums to 1 over every candidate considered.
+    pub confidence: f64,
+}
+
+/// Most likely bags for a single game, best first.
+pub fn infer_game(game: &Game, options: &InferenceOptions) -> Vec<Candidate> {
+    infer(std::slice::from_ref(game), options)
+}
+
+/// Most likely single bag behind all `games`, best first.
+pub fn infer_all(games: &[Game], options: &InferenceOptions) -> Vec<Candidate> {
+    infer(games, options)
+}
+
+fn infer(games: &[Game], options: &InferenceOptions) -> Vec<Candidate> {
+    let minimum = games.iter().fold(Draw::default(), |minimum, game| {
+        minimum.update_with(&game.min_draw())
+    });
+    // Candidates are enumerated over the colours in alphabetical order.
+    let mut cubes: Vec<(&str, usize)> = minimum.cubes().collect();
+    cubes.sort();
+    let colours: Vec<&str> = cubes.iter().map(|(colour, _)| *colour).collect();
+    let lower: Vec<usize> = cubes.iter().map(|(_, count)| *count).collect();
+    let draws: Vec<Vec<usize>> = games
+        .iter()
+        .flat_map(|game| &game.draws)
+        .map(|draw| {
+            colours
+                .iter()
+                .map(|colour| draw.count(colour).unwrap_or(0))
+                .collect()
+        })
+        .collect();
+    let ln_factorial = ln_factorials(lower.iter().sum::<usize>() + colours.len() * options.slack);
+
+    // Only the `top` best candidates are kept; the normaliser is summed
+    // relative to the best log-likelihood seen so far.
+    let mut ranked: BinaryHeap<Ranked> = BinaryHeap::new();
+    let mut best = f64::NEG_INFINITY;
+    let mut total = 0.0;
+    let mut counts = lower.clone();
+    loop {
+        let log_likelihood: f64 = draws
+            .iter()
+            .map(|shown| ln_probability(&counts, shown, &ln_factorial))
+            .sum();
+        if log_likelihood > best {
+            total = total * (best - log_likelihood).exp() + 1.0;
+            best = log_likelihood;
+        } else {
+            total += (log_likelihood - best).exp();
+        }
+        keep(&mut ranked, options.top, log_likelihood, &counts);
+        if !next_counts(&mut counts, &lower, options.slack) {
+            break;
+        }
+    }
+
+    ranked
+        .into_sorted_vec()
+        .into_iter()
+        .map(|ranked| Candidate {
+            bag: BagConfig::new(colours.iter().copied().zip(ranked.counts)),
+            log_likelihood: ranked.log_likelihood,
+            confidence: (ranked.log_likelihood - best).exp() / total,
+        })
+        .collect()
+}
+
+/// A scored candidate; orders better candidates first.
+struct Ranked {
+    log_likelihood: f64,
+    counts: Vec<usize>,
+}
+
+impl Ranked {
+    /// Equally likely bags are ranked smallest first.
+    fn rank(&self, log_likelihood: f64, counts: &[usize]) -> Ordering {
+        log_likelihood
+            .total_cmp(&self.log_likelihood)
+            .then_with(|| self.counts.iter().sum::<usize>().cmp(&counts.iter().sum()))
+            .then_with(|| self.counts.as_slice().cmp(counts))
+    }
+}
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Ranked) -> Ordering {
+        self.rank(other.log_likelihood, &other.counts)
+    }
+}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Ranked) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Ranked {
+    fn eq(&self, other: &Ranked) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Ranked {}
+
+/// Adds the candidate to `ranked` if it is among the `top` best, the worst
+/// one being at the top of the heap.
+fn keep(ranked: &mut BinaryHeap<Ranked>, top: usize, log_likelihood: f64, counts: &[usize]) {
+    if top == 0 {
+        return;
+    }
+    if ranked.len() == top {
+        let worst = ranked.peek().expect("a full heap has a worst candidate");
+        if worst.rank(log_likelihood, counts) != Ordering::Greater {
+            return;
+        }
+        ranked.pop();
+    }
+    ranked.push(Ranked {
+        log_likelihood,
+        counts: counts.to_vec(),
+    });
+}
+
+/// Log probability of drawing `shown` from a bag holding `counts`.
+fn ln_probability(counts: &[usize], shown: &[usize], ln_factorial: &[f64]) -> f64 {
+    let ln_choose = |n: usize, k: usize| ln_factorial[n] - ln_factorial[k] - ln_factorial[n - k];
+    let size = counts.iter().sum();
+    let handful = shown.iter().sum();
+    counts
+        .iter()
+        .zip(shown)
+        .map(|(&count, &shown)| ln_choose(count, shown))
+        .sum::<f64>()
+        - ln_choose(size, handful)
+}
+
+fn ln_factorials(up_to: usize) -> Vec<f64> {
+    let mut table = vec![0.0; up_to + 1];
+    for n in 1..=up_to {
+        table[n] = table[n - 1] + (n as f64).ln();
+    }
+    table
+}
+
+/// Advances `counts` like an odometer over `lower..=lower + slack`; false
+/// once every combination has been visited.
+fn next_counts(counts: &mut [usize], lower: &[usize], slack: usize) -> bool {
+    for (count, &lower) in counts.iter_mut().zip(lower) {
+        if *count < lower + slack {
+            *count += 1;
+            return true;
+        }
+        *count = lower;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(slack: usize) -> InferenceOptions {
+        InferenceOptions {
+            slack,
+            top: usize::MAX,
+        }
+    }
+
+    #[test]
+    fn test_exactly_explained_game_ranks_minimum_first() {
+        let game =
+            Game::from_string("Game 1: 2 red, 2 blue; 2 red, 2 blue; 2 blue, 2 red").unwrap();
+
+        let candidates = infer_game(&game, &options(3));
+
+        assert_eq!(candidates.len(), 16);
+        assert_eq!(candidates[0].bag, BagConfig::new([("blue", 2), ("red", 2)]));
+        assert!(candidates[0].confidence > candidates[1].confidence);
+    }
+
+    #[test]
+    fn test_proportions_follow_observations() {
+        let draws = ["1 red"; 10].join("; ");
+        let game = Game::from_string(&format!("Game 1: {}; 1 blue", draws)).unwrap();
+
+        let candidates = infer_game(&game, &options(12));
+
+        assert_eq!(
+            candidates[0].bag,
+            BagConfig::new([("blue", 1), ("red", 10)])
+        );
+    }
+
+    #[test]
+    fn test_confidences_sum_to_one() {
+        let game = Game::from_string("Game 1: 3 red, 1 green; 1 blue, 2 green").unwrap();
+
+        let total: f64 = infer_game(&game, &options(4))
+            .iter()
+            .map(|candidate| candidate.confidence)
+            .sum();
+
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_top_candidates_are_the_best_of_all() {
+        let game = Game::from_string("Game 1: 3 red, 1 green; 1 blue, 2 green").unwrap();
+
+        let all = infer_game(&game, &options(4));
+        let top = infer_game(&game, &InferenceOptions { slack: 4, top: 3 });
+
+        assert_eq!(top, all[..3]);
+    }
+
+    #[test]
+    fn test_single_colour_ties_prefer_smaller_bag() {
+        let game = Game::from_string("Game 1: 3 red").unwrap();
+
+        let candidates = infer_game(&game, &InferenceOptions { slack: 4, top: 2 });
+
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].bag, BagConfig::new([("red", 3)]));
+        assert_eq!(candidates[1].bag, BagConfig::new([("red", 4)]));
+        assert!((candidates[0].confidence - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_all_games_share_one_bag() {
+        let games = crate::parse("Game 1: 4 red, 1 blue\nGame 2: 1 red, 3 blue")
+            .unwrap()
+            .value;
+
+        let candidates = infer_all(&games, &options(0));
+
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].bag, BagConfig::new([("blue", 3), ("red", 4)]));
+        assert_eq!(candidates[0].confidence, 1.0);
+    }
+}
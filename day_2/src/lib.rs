@@ -1,2 +1,181 @@
+use aoc_common::diagnostic::{Diagnose, Diagnostic};
+use aoc_common::Solution;
+use bag::BagConfig;
+use part_1::Game;
+use std::fmt;
+use validation::{Issues, Validated, Validation};
+
+pub mod bag;
+pub mod inference;
 pub mod part_1;
 pub mod part_2;
+pub mod query;
+#[cfg(feature = "serde")]
+pub mod records;
+pub mod simulate;
+pub mod validation;
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Parsed = Vec<Game>;
+    type Answer = usize;
+    type Error = GameParseError;
+
+    /// Issues tolerated by [`parse`] are errors here.
+    fn parse(input: &str) -> Result<Vec<Game>, GameParseError> {
+        parse_with(input, Validation::Strict).map(|games| games.value)
+    }
+
+    fn part_one(games: &Vec<Game>) -> Result<usize, GameParseError> {
+        Ok(part_1::sum_of_valid_ids(games, &BagConfig::puzzle()))
+    }
+
+    fn part_two(games: &Vec<Game>) -> Result<usize, GameParseError> {
+        Ok(part_2::sum_of_powers(games))
+    }
+}
+
+/// Games of `input`, with the issues found in it as warnings.
+pub fn parse(input: &str) -> Result<Validated<Vec<Game>>, GameParseError> {
+    parse_with(input, Validation::Lenient)
+}
+
+/// With [`Validation::Strict`] the first issue is an error, reported in input
+/// order along with the lines that fail to parse.
+pub fn parse_with(
+    input: &str,
+    validation: Validation,
+) -> Result<Validated<Vec<Game>>, GameParseError> {
+    let mut issues = Issues::numbered(validation);
+    let games = input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| Game::parse(&LineContext::new(index + 1, line), &mut issues))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Validated {
+        value: games,
+        warnings: issues.into_warnings(),
+    })
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub source: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GameParseError {
+    MissingSeparator(Position),
+    InvalidId(Position),
+    MalformedCube(Position),
+    InvalidCount(Position),
+    UnknownColour(Position),
+    DuplicateColour(Position),
+    ZeroCount(Position),
+    AbsurdCount(Position),
+    EmptyDraw(Position),
+    NonSequentialId(Position),
+}
+
+impl GameParseError {
+    pub fn position(&self) -> &Position {
+        match self {
+            GameParseError::MissingSeparator(position)
+            | GameParseError::InvalidId(position)
+            | GameParseError::MalformedCube(position)
+            | GameParseError::InvalidCount(position)
+            | GameParseError::UnknownColour(position)
+            | GameParseError::DuplicateColour(position)
+            | GameParseError::ZeroCount(position)
+            | GameParseError::AbsurdCount(position)
+            | GameParseError::EmptyDraw(position)
+            | GameParseError::NonSequentialId(position) => position,
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self {
+            GameParseError::MissingSeparator(_) => "expected `Game <id>: <draws>`",
+            GameParseError::InvalidId(_) => "invalid game id",
+            GameParseError::MalformedCube(_) => "expected `<count> <colour>`",
+            GameParseError::InvalidCount(_) => "invalid cube count",
+            GameParseError::UnknownColour(_) => "unknown colour",
+            GameParseError::DuplicateColour(_) => "colour appears twice in one draw",
+            GameParseError::ZeroCount(_) => "draw shows zero cubes of a colour",
+            GameParseError::AbsurdCount(_) => "implausibly many cubes",
+            GameParseError::EmptyDraw(_) => "empty draw",
+            GameParseError::NonSequentialId(_) => "game id out of sequence",
+        }
+    }
+
+    fn hint(&self) -> String {
+        match self {
+            GameParseError::DuplicateColour(_) => "list every colour once per draw".to_string(),
+            GameParseError::ZeroCount(_) => "leave out colours that were not shown".to_string(),
+            GameParseError::AbsurdCount(_) => format!(
+                "no draw shows more than {} cubes of a colour",
+                validation::MAX_COUNT
+            ),
+            GameParseError::EmptyDraw(_) => "remove the extra `;`".to_string(),
+            GameParseError::NonSequentialId(_) => {
+                "games are numbered 1, 2, 3, ... in order".to_string()
+            }
+            _ => "expected `Game <id>: <count> <colour>, ...; <count> <colour>, ...`".to_string(),
+        }
+    }
+}
+
+impl Diagnose for GameParseError {
+    fn diagnostic(&self) -> Diagnostic {
+        let position = self.position();
+        Diagnostic::new(self.reason())
+            .at(
+                position.line,
+                position.column,
+                position.text.chars().count().max(1),
+                &position.source,
+            )
+            .with_hint(self.hint())
+    }
+}
+
+impl fmt::Display for GameParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.diagnostic())
+    }
+}
+
+impl std::error::Error for GameParseError {}
+
+/// The line being parsed, used to locate the tokens that fail to parse.
+pub(crate) struct LineContext<'a> {
+    number: usize,
+    line: &'a str,
+}
+
+impl<'a> LineContext<'a> {
+    pub(crate) fn new(number: usize, line: &'a str) -> LineContext<'a> {
+        LineContext { number, line }
+    }
+
+    pub(crate) fn line(&self) -> &'a str {
+        self.line
+    }
+
+    /// `token` has to be a slice of the context line.
+    pub(crate) fn position(&self, token: &str) -> Position {
+        Position {
+            line: self.number,
+            column: aoc_common::column_of(self.line, token),
+            text: token.to_string(),
+            source: self.line.to_string(),
+        }
+    }
+}
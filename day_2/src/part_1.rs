@@ -1,48 +1,100 @@
-use std::fmt::Error;
-#[derive(Default)]
+use crate::bag::BagConfig;
+use crate::validation::{Issues, Validated, Validation};
+use crate::{GameParseError, LineContext};
+use std::fmt;
+
+/// Cubes shown in one handful, by colour name, in the order they were shown.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Draw {
-    red: Option<usize>,
-    green: Option<usize>,
-    blue: Option<usize>,
+    cubes: Vec<(String, usize)>,
 }
 
 impl Draw {
-    pub fn new(red: Option<usize>, green: Option<usize>, blue: Option<usize>) -> Draw {
-        Draw { red, green, blue }
+    /// A colour given twice keeps its first place and its last count.
+    pub fn new<C: Into<String>>(cubes: impl IntoIterator<Item = (C, usize)>) -> Draw {
+        let mut draw = Draw::default();
+        for (colour, count) in cubes {
+            draw.set(colour.into(), count);
+        }
+        draw
     }
 
-    pub fn red(&self) -> Option<usize> {
-        self.red
+    /// Cubes of `colour` shown, `None` when the colour is not in the draw.
+    pub fn count(&self, colour: &str) -> Option<usize> {
+        self.cubes
+            .iter()
+            .find(|(name, _)| name == colour)
+            .map(|(_, count)| *count)
     }
 
-    pub fn blue(&self) -> Option<usize> {
-        self.blue
+    /// Colours and counts in the order they were shown.
+    pub fn cubes(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.cubes
+            .iter()
+            .map(|(colour, count)| (colour.as_str(), *count))
     }
 
-    pub fn green(&self) -> Option<usize> {
-        self.green
+    pub fn possible(&self, bag: &BagConfig) -> bool {
+        self.cubes()
+            .all(|(colour, count)| count <= bag.limit(colour))
     }
 
-    pub fn possible(&self) -> bool {
-        self.red <= Some(12) && self.green <= Some(13) && self.blue <= Some(14)
+    /// Any issue in `draw` is an error, as with [`Validation::Strict`].
+    pub fn from_string(draw: &str) -> Result<Draw, GameParseError> {
+        let mut issues = Issues::new(Validation::Strict);
+        Draw::parse(draw, &LineContext::new(1, draw), &mut issues)
     }
 
-    pub fn from_string(draw: &str) -> Draw {
+    fn parse(
+        draw: &str,
+        context: &LineContext,
+        issues: &mut Issues,
+    ) -> Result<Draw, GameParseError> {
         let mut result = Draw::default();
-        for cube in draw.split(", ") {
-            let (amount, color) = cube.split_once(' ').unwrap();
-            let value: usize = amount.parse::<usize>().unwrap();
-            match &color[0..1] {
-                "r" => result.red = Some(value),
-                "b" => result.blue = Some(value),
-                "g" => result.green = Some(value),
-                _ => panic!("Something is wrong"),
+        for cube in draw.split(',').map(str::trim) {
+            let (amount, colour) = cube
+                .split_once(' ')
+                .ok_or_else(|| GameParseError::MalformedCube(context.position(cube)))?;
+            let value: usize = amount
+                .parse::<usize>()
+                .map_err(|_| GameParseError::InvalidCount(context.position(amount)))?;
+            issues.count(value, amount, context)?;
+            if !is_colour_name(colour) {
+                return Err(GameParseError::UnknownColour(context.position(colour)));
             }
+            if result.count(colour).is_some() {
+                issues.report(GameParseError::DuplicateColour(context.position(colour)))?;
+            }
+            result.set(colour.to_string(), value);
+        }
+        Ok(result)
+    }
+
+    pub(crate) fn set(&mut self, colour: String, count: usize) {
+        match self.cubes.iter_mut().find(|(name, _)| *name == colour) {
+            Some((_, existing)) => *existing = count,
+            None => self.cubes.push((colour, count)),
         }
-        result
     }
 }
 
+impl fmt::Display for Draw {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cubes: Vec<_> = self
+            .cubes()
+            .map(|(colour, count)| format!("{} {}", count, colour))
+            .collect();
+        write!(f, "{}", cubes.join(", "))
+    }
+}
+
+/// Colour names are single words made of letters.
+pub(crate) fn is_colour_name(colour: &str) -> bool {
+    !colour.is_empty() && colour.chars().all(char::is_alphabetic)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     id: usize,
     pub draws: Vec<Draw>,
@@ -52,92 +104,240 @@ impl Game {
     pub fn new(id: usize, draws: Vec<Draw>) -> Game {
         Game { id, draws }
     }
-    pub fn valid(&self) -> bool {
-        self.draws.iter().all(|draw| draw.possible())
+    pub fn valid(&self, bag: &BagConfig) -> bool {
+        self.draws.iter().all(|draw| draw.possible(bag))
+    }
+
+    /// Any issue in `line` is an error, as with [`Validation::Strict`];
+    /// the id is not checked against other games.
+    pub fn from_string(line: &str) -> Result<Game, GameParseError> {
+        let mut issues = Issues::new(Validation::Strict);
+        Game::parse(&LineContext::new(1, line), &mut issues)
     }
 
-    pub fn from_string(line: &str) -> Game {
-        let (id, rest) = Game::parse_line(line);
-        let draws = Game::parse_draws(rest.unwrap());
-        Game::new(id, draws)
+    pub(crate) fn parse(
+        context: &LineContext,
+        issues: &mut Issues,
+    ) -> Result<Game, GameParseError> {
+        let (id, rest) = Game::parse_line(context, issues)?;
+        let draws = Game::parse_draws(rest, context, issues)?;
+        Ok(Game::new(id, draws))
     }
 
     pub fn id(&self) -> usize {
         self.id
     }
 
-    fn parse_draws(draws: &str) -> Vec<Draw> {
+    fn parse_draws(
+        draws: &str,
+        context: &LineContext,
+        issues: &mut Issues,
+    ) -> Result<Vec<Draw>, GameParseError> {
         let mut result = Vec::new();
-        for draw in draws.split("; ") {
-            let parsed_draw = Draw::from_string(draw);
+        for draw in draws.split(';') {
+            if draw.trim().is_empty() {
+                issues.report(GameParseError::EmptyDraw(context.position(draw)))?;
+                continue;
+            }
+            let parsed_draw = Draw::parse(draw.trim(), context, issues)?;
             result.push(parsed_draw);
         }
-        result
+        Ok(result)
     }
 
-    fn parse_line(line: &str) -> (usize, Option<&str>) {
-        let mut elements = line.split(": ");
-        let id_part = elements.next().unwrap();
-        let id = id_part.split_once(' ').unwrap().1.parse::<usize>().unwrap();
-        let rest = elements.next();
-        (id, rest)
+    fn parse_line<'a>(
+        context: &LineContext<'a>,
+        issues: &mut Issues,
+    ) -> Result<(usize, &'a str), GameParseError> {
+        let line = context.line();
+        let (id_part, rest) = line
+            .split_once(": ")
+            .ok_or_else(|| GameParseError::MissingSeparator(context.position(line)))?;
+        let (_, raw_id) = id_part
+            .split_once(' ')
+            .ok_or_else(|| GameParseError::InvalidId(context.position(id_part)))?;
+        let id = raw_id
+            .parse::<usize>()
+            .map_err(|_| GameParseError::InvalidId(context.position(raw_id)))?;
+        issues.game_id(id, raw_id, context)?;
+        Ok((id, rest))
     }
 }
 
-pub fn process(input: &str) -> Result<usize, Error> {
-    let result: usize = input
-        .lines()
-        .map(Game::from_string)
-        .filter(|game| game.valid())
-        .map(|game| game.id())
-        .sum();
+/// The puzzle's `Game <id>: <draws>` line.
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let draws: Vec<_> = self.draws.iter().map(Draw::to_string).collect();
+        write!(f, "Game {}: {}", self.id, draws.join("; "))
+    }
+}
+
+/// With [`Validation::Strict`] the first issue is an error, with
+/// [`Validation::Lenient`] issues are returned as warnings.
+pub fn process(
+    input: &str,
+    bag: &BagConfig,
+    validation: Validation,
+) -> Result<Validated<usize>, GameParseError> {
+    let games = crate::parse_with(input, validation)?;
+
+    Ok(Validated {
+        value: sum_of_valid_ids(&games.value, bag),
+        warnings: games.warnings,
+    })
+}
 
-    Ok(result)
+pub fn sum_of_valid_ids(games: &[Game], bag: &BagConfig) -> usize {
+    games
+        .iter()
+        .filter(|game| game.valid(bag))
+        .map(|game| game.id())
+        .sum()
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::simulate::Simulator;
 
     #[test]
     fn test_parse_game_stats_one_draw() {
         let line: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
-        let game = Game::from_string(line);
+        let game = Game::from_string(line).unwrap();
+
+        assert!(game.valid(&BagConfig::puzzle()))
+    }
+
+    #[test]
+    fn test_invalid_count_is_located() {
+        let line = "Game 1: 3 blue, x red; 2 green";
+        let result = Game::from_string(line);
+
+        assert_eq!(
+            result.err(),
+            Some(GameParseError::InvalidCount(crate::Position {
+                line: 1,
+                column: 17,
+                text: "x".to_string(),
+                source: line.to_string(),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_unknown_colour_is_reported() {
+        let result = Draw::from_string("3 r3d");
+
+        assert!(matches!(result, Err(GameParseError::UnknownColour(_))));
+    }
 
-        assert!(game.valid())
+    #[test]
+    fn test_colours_match_exactly() {
+        let draw = Draw::from_string("3 brown, 2 blue, 7 yellow").unwrap();
+
+        assert_eq!(draw.count("brown"), Some(3));
+        assert_eq!(draw.count("blue"), Some(2));
+        assert_eq!(draw.count("yellow"), Some(7));
+        assert_eq!(draw.count("red"), None);
+    }
+
+    #[test]
+    fn test_colour_missing_from_bag_is_impossible() {
+        let game = Game::from_string("Game 1: 1 red; 1 yellow").unwrap();
+
+        assert!(!game.valid(&BagConfig::puzzle()));
+        assert!(game.valid(&BagConfig::puzzle().with_limit("yellow", 1)));
+    }
+
+    #[test]
+    fn test_missing_separator_is_reported_with_line_number() {
+        let input = "Game 1: 3 blue\nGame 2 3 blue";
+        let result = crate::parse(input);
+
+        match result {
+            Err(GameParseError::MissingSeparator(position)) => assert_eq!(position.line, 2),
+            other => panic!("expected MissingSeparator, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_process_with_custom_bag() {
+        let input = "Game 1: 3 blue, 4 red\nGame 2: 1 red, 20 green\nGame 3: 8 green, 6 blue";
+
+        let sum = |bag| process(input, &bag, Validation::Strict).unwrap().value;
+
+        assert_eq!(sum(BagConfig::puzzle()), 1 + 3);
+        assert_eq!(
+            sum(BagConfig::new([("red", 5), ("green", 20), ("blue", 5)])),
+            1 + 2
+        );
+    }
+
+    #[test]
+    fn test_display_is_the_puzzle_line() {
+        let line = "Game 12: 4 red, 3 blue; 2 red, 1 green; 5 brown";
+        let game = Game::from_string(line).unwrap();
+
+        assert_eq!(game.to_string(), line);
+        assert_eq!(game.draws[0].to_string(), "4 red, 3 blue");
+    }
+
+    #[test]
+    fn test_sample_lines_round_trip() {
+        let sample = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+        for line in sample.lines() {
+            assert_eq!(Game::from_string(line).unwrap().to_string(), line);
+        }
+    }
+
+    #[test]
+    fn test_generated_games_round_trip() {
+        let bag = BagConfig::puzzle()
+            .with_limit("yellow", 4)
+            .with_limit("brown", 1);
+        for seed in 0..50 {
+            for game in Simulator::new(bag.clone(), seed).unwrap().games(20) {
+                assert_eq!(Game::from_string(&game.to_string()).unwrap(), game);
+            }
+        }
     }
 
     #[test]
     fn test_game_with_one_draw_is_valid() {
-        let draw = Draw::new(Some(1), None, None);
+        let draw = Draw::new([("red", 1)]);
         let game = Game::new(1, vec![draw]);
 
-        assert!(game.valid());
+        assert!(game.valid(&BagConfig::puzzle()));
     }
 
     #[test]
     fn test_game_with_one_draw_is_invalid() {
-        let draw = Draw::new(Some(17), None, None);
+        let draw = Draw::new([("red", 17)]);
         let game = Game::new(1, vec![draw]);
 
-        assert!(!game.valid());
+        assert!(!game.valid(&BagConfig::puzzle()));
     }
     #[test]
     fn test_game_with_many_draws_is_valid() {
-        let draw1 = Draw::new(Some(12), Some(13), Some(14));
-        let draw2 = Draw::new(Some(1), Some(12), Some(10));
+        let draw1 = Draw::new([("red", 12), ("green", 13), ("blue", 14)]);
+        let draw2 = Draw::new([("red", 1), ("green", 12), ("blue", 10)]);
 
         let game = Game::new(1, vec![draw1, draw2]);
 
-        assert!(game.valid());
+        assert!(game.valid(&BagConfig::puzzle()));
     }
     #[test]
     fn test_game_with_many_draws_is_invalid() {
-        let draw1 = Draw::new(Some(12), Some(13), Some(14));
-        let draw2 = Draw::new(Some(78), Some(12), Some(10));
+        let draw1 = Draw::new([("red", 12), ("green", 13), ("blue", 14)]);
+        let draw2 = Draw::new([("red", 78), ("green", 12), ("blue", 10)]);
 
         let game = Game::new(1, vec![draw1, draw2]);
 
-        assert!(!game.valid());
+        assert!(!game.valid(&BagConfig::puzzle()));
     }
 }
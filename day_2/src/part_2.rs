@@ -1,6 +1,8 @@
+use crate::bag::BagConfig;
 use crate::part_1::*;
+use crate::validation::Validation;
+use crate::GameParseError;
 use std::cmp::max;
-use std::fmt::Error;
 
 impl Game {
     pub fn min_draw(&self) -> Draw {
@@ -12,30 +14,108 @@ impl Game {
         result
     }
 
+    /// Power of the minimum set over every colour shown in the game.
     pub fn power_of_set(&self) -> usize {
         self.min_draw().power_of_draw()
     }
+
+    /// Same as [`power_of_set`](Self::power_of_set), `None` on overflow.
+    pub fn checked_power_of_set(&self) -> Option<usize> {
+        self.min_draw().checked_power()
+    }
+
+    /// Power of the minimum set over the colours of `bag`.
+    pub fn power_in(&self, bag: &BagConfig) -> usize {
+        self.min_draw().power_in(bag)
+    }
 }
 
 impl Draw {
+    /// Per colour, the larger count of the two draws. Colours keep the order
+    /// of `self`, those only in `other` follow in their own order.
     pub fn update_with(&self, other: &Draw) -> Draw {
-        let red = max(self.red(), other.red());
-        let blue = max(self.blue(), other.blue());
-        let green = max(self.green(), other.green());
+        let mut result = self.clone();
+        for (colour, count) in other.cubes() {
+            let larger = max(result.count(colour).unwrap_or(0), count);
+            result.set(colour.to_string(), larger);
+        }
 
-        Draw::new(red, green, blue)
+        result
     }
+
+    /// Product of the counts of every colour in the draw; 0 for an empty draw.
+    /// Saturates at `usize::MAX`.
     pub fn power_of_draw(&self) -> usize {
-        self.blue().unwrap() * self.green().unwrap() * self.red().unwrap()
+        self.checked_power().unwrap_or(usize::MAX)
+    }
+
+    /// Same as [`power_of_draw`](Self::power_of_draw), `None` on overflow.
+    pub fn checked_power(&self) -> Option<usize> {
+        if self.cubes().next().is_none() {
+            return Some(0);
+        }
+        self.cubes()
+            .try_fold(1usize, |power, (_, count)| power.checked_mul(count))
+    }
+
+    /// Product of the counts of every colour of `bag`, colours missing from
+    /// the draw counting as 0; other colours in the draw are ignored.
+    /// Saturates at `usize::MAX`.
+    pub fn power_in(&self, bag: &BagConfig) -> usize {
+        bag.limits()
+            .map(|(colour, _)| self.count(colour).unwrap_or(0))
+            .fold(1, usize::saturating_mul)
     }
 }
 
-pub fn process(input: &str) -> Result<usize, Error> {
-    let result: usize = input
-        .lines()
-        .map(Game::from_string)
-        .map(|game| game.power_of_set())
-        .sum();
+/// Any issue in `input` is an error, as with [`Validation::Strict`].
+pub fn process(input: &str) -> Result<usize, GameParseError> {
+    let games = crate::parse_with(input, Validation::Strict)?;
+
+    Ok(sum_of_powers(&games.value))
+}
 
-    Ok(result)
+pub fn sum_of_powers(games: &[Game]) -> usize {
+    games.iter().map(|game| game.power_of_set()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case::puzzle("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green", 48)]
+    #[case::extra_colour("Game 1: 2 red, 3 brown, 1 green; 4 red, 1 yellow, 5 blue", 60)]
+    #[case::missing_colour("Game 1: 2 red, 3 brown; 4 red, 1 yellow", 12)]
+    #[case::single_colour("Game 1: 5 green; 7 green", 7)]
+    fn test_power_of_set(#[case] line: &str, #[case] expected: usize) {
+        let game = Game::from_string(line).unwrap();
+
+        assert_eq!(game.power_of_set(), expected);
+    }
+
+    #[test]
+    fn test_power_in_custom_bag() {
+        let game = Game::from_string("Game 1: 2 red, 3 brown; 4 red, 1 yellow").unwrap();
+        let bag = BagConfig::new([("red", 5), ("brown", 5), ("yellow", 5)]);
+
+        assert_eq!(game.power_in(&bag), 12);
+        assert_eq!(game.power_in(&bag.with_limit("black", 1)), 0);
+    }
+
+    #[test]
+    fn test_min_draw_keeps_every_colour() {
+        let game = Game::from_string("Game 1: 2 red, 3 brown; 4 red, 1 yellow").unwrap();
+
+        assert_eq!(
+            game.min_draw(),
+            Draw::new([("red", 4), ("brown", 3), ("yellow", 1)])
+        );
+    }
+
+    #[test]
+    fn test_empty_draw_has_no_power() {
+        assert_eq!(Draw::default().power_of_draw(), 0);
+    }
 }
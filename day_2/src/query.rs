@@ -0,0 +1,658 @@
+//! A small query language over parsed games.
+//!
+//! A query either filters games or aggregates a value over them:
+//!
+//! ```text
+//! any(blue > red)                          games where blue ever exceeded red
+//! max green where id in 10..50             most green cubes in games 10 to 49
+//! sum id where red <= 12 and blue <= 14    part 1 with a partial bag
+//! count where not all(red > 0)
+//! ```
+//!
+//! For a game, a colour name is the most cubes of that colour shown in one
+//! draw (its minimum bag); `min(c)`, `max(c)` and `sum(c)` aggregate the
+//! colour over the draws, and `id`, `draws` and `power` are the game's id,
+//! number of draws and power. Inside `any(...)` and `all(...)` a colour name
+//! is its count in the draw at hand. Conditions compare values with
+//! `< <= > >= == !=`, test `id in a..b` or `id in a..=b`, and combine with
+//! `and`, `or`, `not` and parentheses. Absent colours count as 0.
+
+use crate::part_1::{Draw, Game};
+use aoc_common::diagnostic::{Diagnose, Diagnostic};
+use std::fmt;
+use std::str::FromStr;
+
+const KEYWORDS: [&str; 14] = [
+    "and", "or", "not", "where", "in", "any", "all", "id", "draws", "power", "max", "min", "sum",
+    "count",
+];
+
+const SYMBOLS: [(&str, Kind); 11] = [
+    ("..=", Kind::Range { inclusive: true }),
+    ("..", Kind::Range { inclusive: false }),
+    ("<=", Kind::Compare(Comparison::LessOrEqual)),
+    (">=", Kind::Compare(Comparison::GreaterOrEqual)),
+    ("==", Kind::Compare(Comparison::Equal)),
+    ("!=", Kind::Compare(Comparison::NotEqual)),
+    ("<", Kind::Compare(Comparison::Less)),
+    (">", Kind::Compare(Comparison::Greater)),
+    ("=", Kind::Compare(Comparison::Equal)),
+    ("(", Kind::Open),
+    (")", Kind::Close),
+];
+
+/// Runs `query` against `games`.
+pub fn query(games: &[Game], query: &str) -> Result<QueryResult, QueryError> {
+    query
+        .parse::<Query>()?
+        .evaluate(games)
+        .map_err(|Overflow| QueryError::new("value too large", query, 0, query.len()))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    /// Ids of the games satisfying the condition.
+    Filter(Condition),
+    /// An aggregate over the games satisfying the filter, all games without
+    /// one.
+    Aggregate {
+        aggregate: Aggregate,
+        filter: Option<Condition>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Aggregate {
+    Max(Value),
+    Min(Value),
+    Sum(Value),
+    Count,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+    Compare(Value, Comparison, Value),
+    InRange {
+        value: Value,
+        start: usize,
+        end: usize,
+        inclusive: bool,
+    },
+    /// Holds for at least one draw of the game.
+    Any(Box<Condition>),
+    /// Holds for every draw of the game.
+    All(Box<Condition>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(usize),
+    Id,
+    Draws,
+    Power,
+    Colour(String),
+    Max(String),
+    Min(String),
+    Sum(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryResult {
+    Games(Vec<usize>),
+    /// `None` for the maximum or minimum of no games.
+    Value(Option<usize>),
+}
+
+impl Query {
+    pub fn evaluate(&self, games: &[Game]) -> Result<QueryResult, Overflow> {
+        match self {
+            Query::Filter(condition) => {
+                let mut ids = Vec::new();
+                for game in games {
+                    if condition.holds(game, None)? {
+                        ids.push(game.id());
+                    }
+                }
+                Ok(QueryResult::Games(ids))
+            }
+            Query::Aggregate { aggregate, filter } => {
+                let mut selected = Vec::new();
+                for game in games {
+                    if filter.as_ref().map_or(Ok(true), |f| f.holds(game, None))? {
+                        selected.push(game);
+                    }
+                }
+                let values = |value: &Value| -> Result<Vec<usize>, Overflow> {
+                    selected.iter().map(|game| value.of(game, None)).collect()
+                };
+                Ok(QueryResult::Value(match aggregate {
+                    Aggregate::Max(value) => values(value)?.into_iter().max(),
+                    Aggregate::Min(value) => values(value)?.into_iter().min(),
+                    Aggregate::Sum(value) => Some(checked_sum(values(value)?)?),
+                    Aggregate::Count => Some(selected.len()),
+                }))
+            }
+        }
+    }
+}
+
+/// A value of the query does not fit in a `usize`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+fn checked_sum(values: impl IntoIterator<Item = usize>) -> Result<usize, Overflow> {
+    values
+        .into_iter()
+        .try_fold(0usize, usize::checked_add)
+        .ok_or(Overflow)
+}
+
+impl Condition {
+    fn holds(&self, game: &Game, draw: Option<&Draw>) -> Result<bool, Overflow> {
+        Ok(match self {
+            Condition::And(left, right) => left.holds(game, draw)? && right.holds(game, draw)?,
+            Condition::Or(left, right) => left.holds(game, draw)? || right.holds(game, draw)?,
+            Condition::Not(condition) => !condition.holds(game, draw)?,
+            Condition::Compare(left, comparison, right) => {
+                comparison.holds(left.of(game, draw)?, right.of(game, draw)?)
+            }
+            Condition::InRange {
+                value,
+                start,
+                end,
+                inclusive,
+            } => {
+                let value = value.of(game, draw)?;
+                match inclusive {
+                    true => (*start..=*end).contains(&value),
+                    false => (*start..*end).contains(&value),
+                }
+            }
+            Condition::Any(condition) => {
+                for draw in &game.draws {
+                    if condition.holds(game, Some(draw))? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+            Condition::All(condition) => {
+                for draw in &game.draws {
+                    if !condition.holds(game, Some(draw))? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+        })
+    }
+}
+
+impl Value {
+    fn of(&self, game: &Game, draw: Option<&Draw>) -> Result<usize, Overflow> {
+        Ok(match self {
+            Value::Number(number) => *number,
+            Value::Id => game.id(),
+            Value::Draws => game.draws.len(),
+            Value::Power => game.checked_power_of_set().ok_or(Overflow)?,
+            Value::Colour(colour) => match draw {
+                Some(draw) => draw.count(colour).unwrap_or(0),
+                None => counts(game, colour).max().unwrap_or(0),
+            },
+            Value::Max(colour) => counts(game, colour).max().unwrap_or(0),
+            Value::Min(colour) => counts(game, colour).min().unwrap_or(0),
+            Value::Sum(colour) => checked_sum(counts(game, colour))?,
+        })
+    }
+}
+
+/// Cubes of `colour` in every draw of `game`.
+fn counts<'a>(game: &'a Game, colour: &'a str) -> impl Iterator<Item = usize> + 'a {
+    game.draws
+        .iter()
+        .map(move |draw| draw.count(colour).unwrap_or(0))
+}
+
+impl Comparison {
+    fn holds(self, left: usize, right: usize) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+        }
+    }
+}
+
+impl fmt::Display for QueryResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueryResult::Games(ids) => {
+                let ids: Vec<_> = ids.iter().map(usize::to_string).collect();
+                write!(f, "{} games: {}", ids.len(), ids.join(", "))
+            }
+            QueryResult::Value(Some(value)) => write!(f, "{}", value),
+            QueryResult::Value(None) => write!(f, "none"),
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(query: &str) -> Result<Query, QueryError> {
+        let mut parser = Parser {
+            tokens: lex(query)?,
+            position: 0,
+            query,
+        };
+        let parsed = parser.query()?;
+        match parser.peek() {
+            Some(token) => Err(parser.error_at(token, "unexpected input after the query")),
+            None => Ok(parsed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Word(String),
+    Number(usize),
+    Compare(Comparison),
+    Open,
+    Close,
+    Range { inclusive: bool },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    kind: Kind,
+    /// Byte range in the query.
+    start: usize,
+    end: usize,
+}
+
+fn lex(query: &str) -> Result<Vec<Token>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    while let Some(ch) = query[start..].chars().next() {
+        let rest = &query[start..];
+        let length_while =
+            |accept: fn(char) -> bool| rest.find(|ch| !accept(ch)).unwrap_or(rest.len());
+        let (kind, length) = if ch.is_whitespace() {
+            start += length_while(char::is_whitespace);
+            continue;
+        } else if ch.is_ascii_digit() {
+            let length = length_while(|ch| ch.is_ascii_digit());
+            let number = rest[..length].parse().map_err(|_| {
+                QueryError::new("number is too large", query, start, start + length)
+            })?;
+            (Kind::Number(number), length)
+        } else if ch.is_alphabetic() {
+            let length = length_while(|ch| ch.is_alphanumeric() || ch == '_');
+            (Kind::Word(rest[..length].to_string()), length)
+        } else {
+            SYMBOLS
+                .iter()
+                .find(|(symbol, _)| rest.starts_with(symbol))
+                .map(|(symbol, kind)| (kind.clone(), symbol.len()))
+                .ok_or_else(|| {
+                    let message = format!("unexpected character `{}`", ch);
+                    QueryError::new(message, query, start, start + ch.len_utf8())
+                })?
+        };
+        tokens.push(Token {
+            kind,
+            start,
+            end: start + length,
+        });
+        start += length;
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    query: &'a str,
+}
+
+impl Parser<'_> {
+    fn query(&mut self) -> Result<Query, QueryError> {
+        let aggregate = match (self.word(0), self.peek_kind(1)) {
+            (Some("max" | "min" | "sum"), Some(Kind::Open)) => None,
+            (Some("max"), _) => Some(Aggregate::Max(self.skip().value()?)),
+            (Some("min"), _) => Some(Aggregate::Min(self.skip().value()?)),
+            (Some("sum"), _) => Some(Aggregate::Sum(self.skip().value()?)),
+            (Some("count"), None) | (Some("count"), Some(Kind::Word(_))) => {
+                self.skip();
+                Some(Aggregate::Count)
+            }
+            _ => None,
+        };
+
+        match aggregate {
+            Some(aggregate) => {
+                let filter = match self.eat_word("where") {
+                    true => Some(self.condition()?),
+                    false => None,
+                };
+                Ok(Query::Aggregate { aggregate, filter })
+            }
+            None => {
+                self.eat_word("where");
+                Ok(Query::Filter(self.condition()?))
+            }
+        }
+    }
+
+    /// `or` binds loosest, then `and`, then `not`.
+    fn condition(&mut self) -> Result<Condition, QueryError> {
+        let mut condition = self.conjunction()?;
+        while self.eat_word("or") {
+            condition = Condition::Or(Box::new(condition), Box::new(self.conjunction()?));
+        }
+        Ok(condition)
+    }
+
+    fn conjunction(&mut self) -> Result<Condition, QueryError> {
+        let mut condition = self.negation()?;
+        while self.eat_word("and") {
+            condition = Condition::And(Box::new(condition), Box::new(self.negation()?));
+        }
+        Ok(condition)
+    }
+
+    fn negation(&mut self) -> Result<Condition, QueryError> {
+        match self.eat_word("not") {
+            true => Ok(Condition::Not(Box::new(self.negation()?))),
+            false => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Condition, QueryError> {
+        if self.peek_kind(0) == Some(&Kind::Open) {
+            self.skip();
+            let condition = self.condition()?;
+            self.expect(Kind::Close, "`)`")?;
+            return Ok(condition);
+        }
+        if let (Some(quantifier @ ("any" | "all")), Some(Kind::Open)) =
+            (self.word(0), self.peek_kind(1))
+        {
+            let any = quantifier == "any";
+            self.skip().skip();
+            let condition = Box::new(self.condition()?);
+            self.expect(Kind::Close, "`)`")?;
+            return Ok(match any {
+                true => Condition::Any(condition),
+                false => Condition::All(condition),
+            });
+        }
+
+        let value = self.value()?;
+        if self.eat_word("in") {
+            let start = self.number()?;
+            let inclusive = match self.peek_kind(0) {
+                Some(Kind::Range { inclusive }) => *inclusive,
+                _ => return Err(self.error_here("expected `..` or `..=`")),
+            };
+            self.skip();
+            let end = self.number()?;
+            return Ok(Condition::InRange {
+                value,
+                start,
+                end,
+                inclusive,
+            });
+        }
+        let comparison = match self.peek_kind(0) {
+            Some(Kind::Compare(comparison)) => *comparison,
+            _ => return Err(self.error_here("expected a comparison or `in`")),
+        };
+        self.skip();
+        Ok(Condition::Compare(value, comparison, self.value()?))
+    }
+
+    fn value(&mut self) -> Result<Value, QueryError> {
+        let token = match self.peek() {
+            Some(token) => token.clone(),
+            None => return Err(self.error_here("expected a value")),
+        };
+        let word = match &token.kind {
+            Kind::Number(number) => {
+                self.skip();
+                return Ok(Value::Number(*number));
+            }
+            Kind::Word(word) => word.as_str(),
+            _ => return Err(self.error_at(&token, "expected a value")),
+        };
+        let value = match word {
+            "id" => Value::Id,
+            "draws" => Value::Draws,
+            "power" => Value::Power,
+            "max" => Value::Max(self.skip().colour_argument()?),
+            "min" => Value::Min(self.skip().colour_argument()?),
+            "sum" => Value::Sum(self.skip().colour_argument()?),
+            colour if !KEYWORDS.contains(&colour) => Value::Colour(colour.to_string()),
+            _ => return Err(self.error_at(&token, "expected a value")),
+        };
+        if !matches!(value, Value::Max(_) | Value::Min(_) | Value::Sum(_)) {
+            self.skip();
+        }
+        Ok(value)
+    }
+
+    fn colour_argument(&mut self) -> Result<String, QueryError> {
+        self.expect(Kind::Open, "`(`")?;
+        let colour = match self.word(0) {
+            Some(colour) if !KEYWORDS.contains(&colour) => colour.to_string(),
+            _ => return Err(self.error_here("expected a colour")),
+        };
+        self.skip();
+        self.expect(Kind::Close, "`)`")?;
+        Ok(colour)
+    }
+
+    fn number(&mut self) -> Result<usize, QueryError> {
+        match self.peek_kind(0) {
+            Some(Kind::Number(number)) => {
+                let number = *number;
+                self.skip();
+                Ok(number)
+            }
+            _ => Err(self.error_here("expected a number")),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_kind(&self, offset: usize) -> Option<&Kind> {
+        self.tokens
+            .get(self.position + offset)
+            .map(|token| &token.kind)
+    }
+
+    fn word(&self, offset: usize) -> Option<&str> {
+        match self.peek_kind(offset) {
+            Some(Kind::Word(word)) => Some(word),
+            _ => None,
+        }
+    }
+
+    fn skip(&mut self) -> &mut Self {
+        self.position += 1;
+        self
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let found = self.word(0) == Some(word);
+        if found {
+            self.skip();
+        }
+        found
+    }
+
+    fn expect(&mut self, kind: Kind, what: &str) -> Result<(), QueryError> {
+        match self.peek_kind(0) == Some(&kind) {
+            true => {
+                self.skip();
+                Ok(())
+            }
+            false => Err(self.error_here(&format!("expected {}", what))),
+        }
+    }
+
+    fn error_at(&self, token: &Token, message: &str) -> QueryError {
+        QueryError::new(message, self.query, token.start, token.end)
+    }
+
+    /// Points at the next token, or just past the end of the query.
+    fn error_here(&self, message: &str) -> QueryError {
+        match self.peek() {
+            Some(token) => self.error_at(token, message),
+            None => QueryError::new(message, self.query, self.query.len(), self.query.len()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    pub message: String,
+    /// 1-based character column in the query.
+    pub column: usize,
+    pub width: usize,
+    pub query: String,
+}
+
+impl QueryError {
+    fn new(message: impl Into<String>, query: &str, start: usize, end: usize) -> QueryError {
+        QueryError {
+            message: message.into(),
+            column: query[..start].chars().count() + 1,
+            width: query[start..end].chars().count().max(1),
+            query: query.to_string(),
+        }
+    }
+}
+
+impl Diagnose for QueryError {
+    fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(self.message.clone())
+            .at(1, self.column, self.width, &self.query)
+            .with_hint("e.g. `any(blue > red)` or `max green where id in 10..50`")
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.diagnostic())
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    const SAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    #[fixture]
+    #[once]
+    fn games() -> Vec<Game> {
+        crate::parse(SAMPLE).unwrap().value
+    }
+
+    #[rstest]
+    #[case::blue_ever_exceeds_red("any(blue > red)", &[1, 2, 3, 4, 5])]
+    #[case::blue_always_exceeds_red("all(blue > red)", &[2])]
+    #[case::minimum_bag("where red > 12 or blue > 14", &[3, 4])]
+    #[case::negation("not (red > 12 or blue > 14) and id != 5", &[1, 2])]
+    #[case::range("id in 2..4", &[2, 3])]
+    #[case::inclusive_range("id in 2..=4 and draws == 3", &[2, 3, 4])]
+    #[case::aggregates_per_game("sum(green) >= 6 and min(red) = 0", &[2])]
+    #[case::unknown_colour("yellow > 0", &[])]
+    fn test_filter(games: &[Game], #[case] query_text: &str, #[case] expected: &[usize]) {
+        assert_eq!(
+            query(games, query_text).unwrap(),
+            QueryResult::Games(expected.to_vec())
+        );
+    }
+
+    #[rstest]
+    #[case::part_one("sum id where red <= 12 and green <= 13 and blue <= 14", Some(8))]
+    #[case::part_two("sum power", Some(2286))]
+    #[case::max_green_in_range("max green where id in 4..=5", Some(3))]
+    #[case::max_over_all("max max(green)", Some(13))]
+    #[case::min_draws("min draws", Some(2))]
+    #[case::count("count where any(red >= 6)", Some(3))]
+    #[case::count_all("count", Some(5))]
+    #[case::empty_max("max green where id > 5", None)]
+    fn test_aggregate(games: &[Game], #[case] query_text: &str, #[case] expected: Option<usize>) {
+        assert_eq!(
+            query(games, query_text).unwrap(),
+            QueryResult::Value(expected)
+        );
+    }
+
+    #[rstest]
+    #[case::missing_value("blue >", 7, "expected a value")]
+    #[case::missing_close("any(blue > red", 15, "expected `)`")]
+    #[case::missing_range("id in 5", 8, "expected `..` or `..=`")]
+    #[case::keyword_as_colour("max(and)", 5, "expected a colour")]
+    #[case::stray_character("blue # 3", 6, "unexpected character `#`")]
+    #[case::trailing_input("blue > 3 red", 10, "unexpected input after the query")]
+    fn test_errors_are_located(
+        #[case] query_text: &str,
+        #[case] column: usize,
+        #[case] message: &str,
+    ) {
+        let error = query_text.parse::<Query>().unwrap_err();
+
+        assert_eq!(error.message, message);
+        assert_eq!(error.column, column);
+    }
+
+    #[rstest]
+    #[case::power("sum power")]
+    #[case::sum_of_counts("sum sum(red)")]
+    #[case::in_condition("power > 1")]
+    fn test_overflow_is_an_error(#[case] query_text: &str) {
+        let input = "Game 1: 99999999999 red, 99999999999 green, 9999999 blue\n\
+                     Game 2: 18446744073709551615 red; 1 red";
+        let games = crate::parse(input).unwrap().value;
+
+        let error = query(&games, query_text).unwrap_err();
+
+        assert_eq!(error.message, "value too large");
+    }
+
+    #[test]
+    fn test_result_display() {
+        assert_eq!(QueryResult::Games(vec![1, 4]).to_string(), "2 games: 1, 4");
+        assert_eq!(QueryResult::Value(None).to_string(), "none");
+    }
+}
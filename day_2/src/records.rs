@@ -0,0 +1,154 @@
+//! CSV form of games: one `game,draw,colour,count` row per colour shown.
+
+use crate::part_1::{Draw, Game};
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CubeRecord {
+    pub game: usize,
+    /// 1-based index of the draw within its game.
+    pub draw: usize,
+    pub colour: String,
+    pub count: usize,
+}
+
+/// A draw is a JSON object from colour to count, in the order shown.
+impl Serialize for Draw {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.cubes())
+    }
+}
+
+impl<'de> Deserialize<'de> for Draw {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Draw, D::Error> {
+        deserializer.deserialize_map(DrawVisitor)
+    }
+}
+
+struct DrawVisitor;
+
+impl<'de> Visitor<'de> for DrawVisitor {
+    type Value = Draw;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a map from colour to count")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Draw, A::Error> {
+        let mut cubes = Vec::new();
+        while let Some(entry) = map.next_entry::<String, usize>()? {
+            cubes.push(entry);
+        }
+        Ok(Draw::new(cubes))
+    }
+}
+
+pub fn records(games: &[Game]) -> Vec<CubeRecord> {
+    let mut records = Vec::new();
+    for game in games {
+        for (index, draw) in game.draws.iter().enumerate() {
+            for (colour, count) in draw.cubes() {
+                records.push(CubeRecord {
+                    game: game.id(),
+                    draw: index + 1,
+                    colour: colour.to_string(),
+                    count,
+                });
+            }
+        }
+    }
+    records
+}
+
+/// Games in order of first appearance; rows of a game may come in any order.
+pub fn from_records(records: impl IntoIterator<Item = CubeRecord>) -> Vec<Game> {
+    let mut order = Vec::new();
+    let mut games: BTreeMap<usize, BTreeMap<usize, Vec<(String, usize)>>> = BTreeMap::new();
+    for record in records {
+        if !games.contains_key(&record.game) {
+            order.push(record.game);
+        }
+        games
+            .entry(record.game)
+            .or_default()
+            .entry(record.draw)
+            .or_default()
+            .push((record.colour, record.count));
+    }
+
+    order
+        .into_iter()
+        .map(|id| {
+            let draws = games.remove(&id).unwrap_or_default();
+            Game::new(id, draws.into_values().map(Draw::new).collect())
+        })
+        .collect()
+}
+
+pub fn to_csv(games: &[Game]) -> Result<String, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for record in records(games) {
+        writer.serialize(record)?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|error| csv::Error::from(error.into_error()))?;
+    Ok(String::from_utf8(bytes).expect("CSV of games is UTF-8"))
+}
+
+pub fn from_csv(csv: &str) -> Result<Vec<Game>, csv::Error> {
+    let records = csv::Reader::from_reader(csv.as_bytes())
+        .deserialize()
+        .collect::<Result<Vec<CubeRecord>, _>>()?;
+    Ok(from_records(records))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bag::BagConfig;
+    use crate::simulate::Simulator;
+
+    const INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green\nGame 2: 1 brown";
+
+    #[test]
+    fn test_csv_rows() {
+        let games = crate::parse(INPUT).unwrap().value;
+
+        assert_eq!(
+            to_csv(&games).unwrap(),
+            "game,draw,colour,count\n1,1,blue,3\n1,1,red,4\n1,2,red,1\n1,2,green,2\n2,1,brown,1\n"
+        );
+    }
+
+    #[test]
+    fn test_json_shape() {
+        let games = crate::parse(INPUT).unwrap().value;
+
+        assert_eq!(
+            serde_json::to_string(&games[1]).unwrap(),
+            r#"{"id":2,"draws":[{"brown":1}]}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&games[0].draws[0]).unwrap(),
+            r#"{"blue":3,"red":4}"#
+        );
+        let draw: Draw = serde_json::from_str(r#"{"red":4,"blue":3}"#).unwrap();
+        assert_eq!(draw.to_string(), "4 red, 3 blue");
+    }
+
+    #[test]
+    fn test_generated_games_round_trip() {
+        let bag = BagConfig::puzzle().with_limit("yellow", 3);
+        for seed in 0..20 {
+            let games = Simulator::new(bag.clone(), seed).unwrap().games(20);
+
+            assert_eq!(from_csv(&to_csv(&games).unwrap()).unwrap(), games);
+            let json = serde_json::to_string(&games).unwrap();
+            assert_eq!(serde_json::from_str::<Vec<Game>>(&json).unwrap(), games);
+        }
+    }
+}
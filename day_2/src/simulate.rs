@@ -0,0 +1,232 @@
+use crate::bag::BagConfig;
+use crate::part_1::{Draw, Game};
+use crate::validation::MAX_COUNT;
+use aoc_common::diagnostic::{Diagnose, Diagnostic};
+use aoc_common::random::Lcg;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Whether cubes go back into the bag between the rounds of a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Replacement {
+    /// Every round draws from the full bag, as in the puzzle.
+    #[default]
+    With,
+    /// Cubes shown stay out until the game ends; a game stops early once the
+    /// bag is empty.
+    Without,
+}
+
+/// Plays seeded random games against a bag.
+pub struct Simulator {
+    bag: BagConfig,
+    replacement: Replacement,
+    max_rounds: usize,
+    rng: Lcg,
+}
+
+impl Simulator {
+    /// Fails for bags whose games the parser would reject: empty bags give
+    /// games without draws, and more than [`MAX_COUNT`] cubes of a colour
+    /// implausible counts.
+    pub fn new(bag: BagConfig, seed: u64) -> Result<Simulator, SimulationError> {
+        if let Some((colour, limit)) = bag.limits().find(|(_, limit)| *limit > MAX_COUNT) {
+            return Err(SimulationError::TooManyCubes {
+                colour: colour.to_string(),
+                limit,
+            });
+        }
+        if bag.limits().all(|(_, limit)| limit == 0) {
+            return Err(SimulationError::EmptyBag);
+        }
+        Ok(Simulator {
+            bag,
+            replacement: Replacement::With,
+            max_rounds: 6,
+            rng: Lcg::new(seed),
+        })
+    }
+
+    pub fn replacement(mut self, replacement: Replacement) -> Simulator {
+        self.replacement = replacement;
+        self
+    }
+
+    /// Games have between 1 and `max_rounds` draws.
+    pub fn max_rounds(mut self, max_rounds: usize) -> Simulator {
+        self.max_rounds = max_rounds.max(1);
+        self
+    }
+
+    pub fn game(&mut self, id: usize) -> Game {
+        let rounds = 1 + self.rng.below(self.max_rounds as u64) as usize;
+        let mut pool = self.full_bag();
+        let mut draws = Vec::new();
+        for _ in 0..rounds {
+            if self.replacement == Replacement::With {
+                pool = self.full_bag();
+            }
+            let left: usize = pool.values().sum();
+            if left == 0 {
+                break;
+            }
+            let handful = 1 + self.rng.below(left as u64) as usize;
+            draws.push(self.draw(&mut pool, handful));
+        }
+        Game::new(id, draws)
+    }
+
+    /// Games numbered from 1.
+    pub fn games(&mut self, count: usize) -> Vec<Game> {
+        (1..=count).map(|id| self.game(id)).collect()
+    }
+
+    /// Puzzle input with `count` games, one line each.
+    pub fn input(&mut self, count: usize) -> String {
+        self.games(count)
+            .iter()
+            .map(|game| format!("{}\n", game))
+            .collect()
+    }
+
+    fn full_bag(&self) -> BTreeMap<String, usize> {
+        self.bag
+            .limits()
+            .map(|(colour, limit)| (colour.to_string(), limit))
+            .collect()
+    }
+
+    /// Takes `handful` cubes out of `pool` one at a time; colours are shown
+    /// in the order they were first picked.
+    fn draw(&mut self, pool: &mut BTreeMap<String, usize>, handful: usize) -> Draw {
+        let mut shown = Draw::default();
+        for _ in 0..handful {
+            let left: usize = pool.values().sum();
+            let mut pick = self.rng.below(left as u64) as usize;
+            for (colour, count) in pool.iter_mut() {
+                if pick < *count {
+                    *count -= 1;
+                    let count = shown.count(colour).unwrap_or(0);
+                    shown.set(colour.clone(), count + 1);
+                    break;
+                }
+                pick -= *count;
+            }
+        }
+        shown
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SimulationError {
+    EmptyBag,
+    TooManyCubes { colour: String, limit: usize },
+}
+
+impl Diagnose for SimulationError {
+    fn diagnostic(&self) -> Diagnostic {
+        match self {
+            SimulationError::EmptyBag => Diagnostic::new("the bag holds no cubes")
+                .with_hint("give at least one colour a positive count"),
+            SimulationError::TooManyCubes { colour, limit } => Diagnostic::new(format!(
+                "{} {} cubes are too many to simulate",
+                limit, colour
+            ))
+            .with_hint(format!("bags hold at most {} cubes of a colour", MAX_COUNT)),
+        }
+    }
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.diagnostic())
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::Validation;
+    use rstest::*;
+
+    #[test]
+    fn test_same_seed_same_games() {
+        let input = |seed| Simulator::new(BagConfig::puzzle(), seed).unwrap().input(20);
+
+        assert_eq!(input(7), input(7));
+        assert_ne!(input(7), input(8));
+    }
+
+    #[rstest]
+    #[case::with_replacement(Replacement::With)]
+    #[case::without_replacement(Replacement::Without)]
+    fn test_generated_lines_parse_back(#[case] replacement: Replacement) {
+        let mut simulator = Simulator::new(BagConfig::puzzle(), 3)
+            .unwrap()
+            .replacement(replacement);
+        let games = simulator.games(200);
+
+        for game in &games {
+            assert_eq!(&Game::from_string(&game.to_string()).unwrap(), game);
+        }
+    }
+
+    #[rstest]
+    #[case::puzzle(BagConfig::puzzle(), Replacement::With)]
+    #[case::single_cube(BagConfig::new([("red", 1), ("green", 0)]), Replacement::Without)]
+    #[case::largest(BagConfig::new([("red", MAX_COUNT)]), Replacement::With)]
+    fn test_generated_input_parses_strictly(
+        #[case] bag: BagConfig,
+        #[case] replacement: Replacement,
+    ) {
+        let input = Simulator::new(bag, 9)
+            .unwrap()
+            .replacement(replacement)
+            .input(100);
+
+        assert!(crate::parse_with(&input, Validation::Strict).is_ok());
+    }
+
+    #[rstest]
+    #[case::empty(BagConfig::new([("red", 0), ("green", 0)]), SimulationError::EmptyBag)]
+    #[case::too_many(
+        BagConfig::puzzle().with_limit("red", MAX_COUNT + 1),
+        SimulationError::TooManyCubes { colour: "red".to_string(), limit: MAX_COUNT + 1 }
+    )]
+    fn test_reject_bags_the_parser_would_not_accept(
+        #[case] bag: BagConfig,
+        #[case] expected: SimulationError,
+    ) {
+        assert_eq!(Simulator::new(bag, 1).err(), Some(expected));
+    }
+
+    #[test]
+    fn test_games_drawn_with_replacement_fit_the_bag() {
+        let bag = BagConfig::new([("red", 3), ("green", 1), ("blue", 5), ("brown", 2)]);
+        let games = Simulator::new(bag.clone(), 11).unwrap().games(500);
+
+        assert!(games.iter().all(|game| game.valid(&bag)));
+        assert!(games.iter().all(|game| game.power_in(&bag) <= 3 * 5 * 2));
+    }
+
+    #[test]
+    fn test_games_drawn_without_replacement_use_each_cube_once() {
+        let bag = BagConfig::new([("red", 4), ("blue", 6)]);
+        let mut simulator = Simulator::new(bag.clone(), 5)
+            .unwrap()
+            .replacement(Replacement::Without)
+            .max_rounds(10);
+
+        for game in simulator.games(300) {
+            let total = game.draws.iter().fold(Draw::default(), |total, draw| {
+                Draw::new(
+                    ["red", "blue"]
+                        .map(|c| (c, total.count(c).unwrap_or(0) + draw.count(c).unwrap_or(0))),
+                )
+            });
+            assert!(total.possible(&bag), "{}", game);
+        }
+    }
+}
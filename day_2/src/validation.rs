@@ -0,0 +1,196 @@
+//! Issues in game records the parser tolerates: colours listed twice in a
+//! draw, zero or implausible counts, empty draws and game ids out of
+//! sequence. They are found while parsing, see [`parse_with`](crate::parse_with).
+
+use crate::{GameParseError, LineContext};
+
+/// Counts above this are reported as [`GameParseError::AbsurdCount`].
+pub const MAX_COUNT: usize = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Validation {
+    /// The first issue is an error.
+    Strict,
+    /// Issues are collected as warnings.
+    #[default]
+    Lenient,
+}
+
+/// A result together with the issues tolerated to get it.
+#[derive(Debug, PartialEq)]
+pub struct Validated<T> {
+    pub value: T,
+    pub warnings: Vec<GameParseError>,
+}
+
+/// Issues reported while parsing, in the order of their tokens.
+pub(crate) struct Issues {
+    validation: Validation,
+    /// Id the next game should have; `None` when games are parsed alone.
+    next_id: Option<usize>,
+    found: Vec<GameParseError>,
+}
+
+impl Issues {
+    /// Issues of a single game or draw.
+    pub(crate) fn new(validation: Validation) -> Issues {
+        Issues {
+            validation,
+            next_id: None,
+            found: Vec::new(),
+        }
+    }
+
+    /// Issues of a whole input, whose games are numbered from 1.
+    pub(crate) fn numbered(validation: Validation) -> Issues {
+        Issues {
+            next_id: Some(1),
+            ..Issues::new(validation)
+        }
+    }
+
+    /// Keeps `issue` as a warning, or fails with it in strict mode.
+    pub(crate) fn report(&mut self, issue: GameParseError) -> Result<(), GameParseError> {
+        match self.validation {
+            Validation::Strict => Err(issue),
+            Validation::Lenient => {
+                self.found.push(issue);
+                Ok(())
+            }
+        }
+    }
+
+    /// `raw_id` is the token `id` was parsed from.
+    pub(crate) fn game_id(
+        &mut self,
+        id: usize,
+        raw_id: &str,
+        context: &LineContext,
+    ) -> Result<(), GameParseError> {
+        let Some(expected) = self.next_id.replace(id + 1) else {
+            return Ok(());
+        };
+        match id == expected {
+            true => Ok(()),
+            false => self.report(GameParseError::NonSequentialId(context.position(raw_id))),
+        }
+    }
+
+    /// Reports a zero or implausible `count`, parsed from `amount`.
+    pub(crate) fn count(
+        &mut self,
+        count: usize,
+        amount: &str,
+        context: &LineContext,
+    ) -> Result<(), GameParseError> {
+        match count {
+            0 => self.report(GameParseError::ZeroCount(context.position(amount))),
+            count if count > MAX_COUNT => {
+                self.report(GameParseError::AbsurdCount(context.position(amount)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn into_warnings(self) -> Vec<GameParseError> {
+        self.found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bag::BagConfig;
+    use crate::part_1::process;
+    use rstest::*;
+
+    fn validate(input: &str) -> Vec<GameParseError> {
+        crate::parse(input).unwrap().warnings
+    }
+
+    #[rstest]
+    #[case::duplicate("Game 1: 3 red, 4 red", "red", 18)]
+    #[case::zero("Game 1: 0 red, 4 blue", "0", 9)]
+    #[case::absurd("Game 1: 1001 red", "1001", 9)]
+    #[case::empty_draw("Game 1: 3 red;; 2 blue", "", 15)]
+    #[case::non_sequential("Game 2: 3 red", "2", 6)]
+    fn test_issue_is_located(#[case] line: &str, #[case] text: &str, #[case] column: usize) {
+        let issues = validate(line);
+
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        assert_eq!(issues[0].position().text, text);
+        assert_eq!(issues[0].position().column, column);
+    }
+
+    #[test]
+    fn test_clean_input_has_no_issues() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green\nGame 2: 1 blue\nGame 3: 20 red";
+
+        assert!(validate(input).is_empty());
+    }
+
+    #[test]
+    fn test_sequence_continues_after_gap() {
+        let issues = validate("Game 1: 1 red\nGame 3: 1 red\nGame 4: 1 red\nGame 4: 1 red");
+
+        let lines: Vec<_> = issues.iter().map(|issue| issue.position().line).collect();
+        assert_eq!(lines, [2, 4]);
+    }
+
+    #[test]
+    fn test_strict_process_rejects_issues() {
+        let input = "Game 1: 3 red, 4 red; 2 blue";
+
+        let result = process(input, &BagConfig::puzzle(), Validation::Strict);
+
+        assert!(matches!(result, Err(GameParseError::DuplicateColour(_))));
+    }
+
+    #[test]
+    fn test_strict_reports_issues_in_input_order() {
+        let input = "Game 1: 1 red\nGame 2 1 red\nGame 3: 1 red, 1 red";
+
+        let result = crate::parse_with(input, Validation::Strict);
+
+        match result {
+            Err(GameParseError::MissingSeparator(position)) => assert_eq!(position.line, 2),
+            other => panic!("expected MissingSeparator, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_lenient_parse_keeps_issues_out_of_games() {
+        let input = "Game 1: 3 red, 4 red;; 0 blue";
+
+        let games = crate::parse(input).unwrap().value;
+
+        assert_eq!(games[0].to_string(), "Game 1: 4 red; 0 blue");
+    }
+
+    #[test]
+    fn test_lenient_process_sums_and_warns() {
+        let input = "Game 1: 3 red, 4 red;; 0 blue\nGame 3: 20 red";
+
+        let result = process(input, &BagConfig::puzzle(), Validation::Lenient).unwrap();
+
+        assert_eq!(result.value, 1);
+        assert!(matches!(
+            result.warnings.as_slice(),
+            [
+                GameParseError::DuplicateColour(_),
+                GameParseError::EmptyDraw(_),
+                GameParseError::ZeroCount(_),
+                GameParseError::NonSequentialId(_),
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_warning_renders_with_hint() {
+        let issue = &validate("Game 1: 3 red, 4 red")[0];
+
+        assert!(issue
+            .to_string()
+            .contains("= hint: list every colour once per draw"));
+    }
+}
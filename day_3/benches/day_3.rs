@@ -0,0 +1,51 @@
+use aoc_common::bench::{bench_solution, Lcg};
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_3::Day3;
+
+const SAMPLE: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+const SYMBOLS: [char; 6] = ['*', '#', '+', '$', '/', '@'];
+
+fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Lcg::new(seed);
+    let mut input = String::new();
+    for _ in 0..size {
+        let mut line = String::new();
+        while line.len() < size - 4 {
+            match rng.below(10) {
+                0..=1 => {
+                    for _ in 0..1 + rng.below(3) {
+                        line.push(char::from(b'0' + rng.below(10) as u8));
+                    }
+                    line.push('.');
+                }
+                2 => line.push(SYMBOLS[rng.below(SYMBOLS.len() as u64) as usize]),
+                _ => line.push('.'),
+            }
+        }
+        // Keep numbers from running into the next row.
+        while line.len() < size {
+            line.push('.');
+        }
+        input.push_str(&line);
+        input.push('\n');
+    }
+    input
+}
+
+fn benches(c: &mut Criterion) {
+    bench_solution::<Day3>(c, "sample", SAMPLE);
+    bench_solution::<Day3>(c, "large", &generate(1_000, 3));
+}
+
+criterion_group!(day_3, benches);
+criterion_main!(day_3);
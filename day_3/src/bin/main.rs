@@ -1,18 +1,7 @@
-use day_3::*;
+use aoc_common::report;
+use day_3::Day3;
+use std::process::ExitCode;
 
-fn main() {
-    let input = include_str!("../../input.txt");
-
-    let result = part_1(input);
-
-    match result {
-        Ok(result) => println!("Result part 1: {}", result),
-        Err(error) => println!("Error part 1: {}", error),
-    }
-
-    let result2 = part_2(input);
-    match result2 {
-        Ok(result) => println!("Result part 2: {}", result),
-        Err(error) => println!("Error part 2: {}", error),
-    }
+fn main() -> ExitCode {
+    report::main::<Day3>(&[1, 2])
 }
@@ -1,28 +1,50 @@
+use aoc_common::diagnostic::{Diagnose, Diagnostic};
+use aoc_common::Solution;
 use std::collections::{HashMap, HashSet};
-use std::{fmt, i32, usize};
+use std::fmt;
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Parsed = Schematics;
+    type Answer = usize;
+    type Error = SchematicsError;
+
+    fn parse(input: &str) -> Result<Schematics, SchematicsError> {
+        parse(input)
+    }
+
+    fn part_one(schema: &Schematics) -> Result<usize, SchematicsError> {
+        Ok(schema.part_numbers_sum())
+    }
+
+    fn part_two(schema: &Schematics) -> Result<usize, SchematicsError> {
+        schema.gear_ratios_sum()
+    }
+}
 
 pub fn part_1(input: &str) -> Result<usize, SchematicsError> {
-    let schema: Schematics = parse(input).unwrap();
-    let result: usize = schema
-        .filter_adjacent()
-        .iter()
-        .map(|part_number| part_number.value())
-        .sum();
-
-    Ok(result)
+    let schema: Schematics = parse(input)?;
+    Ok(schema.part_numbers_sum())
 }
 
 pub fn part_2(input: &str) -> Result<usize, SchematicsError> {
-    let schema = parse(input).unwrap();
-    let result: i32 = schema.gear_ratios().iter().sum();
-    Ok(result as usize)
+    let schema = parse(input)?;
+    schema.gear_ratios_sum()
 }
 
-fn parse(input: &str) -> Result<Schematics, SchematicsError> {
+pub fn parse(input: &str) -> Result<Schematics, SchematicsError> {
     let mut symbol_schematics: Schematics = Schematics::new();
 
     if input.is_empty() {
-        return Err(SchematicsError("Empty input".to_string()));
+        return Err(SchematicsError::new(
+            1,
+            1,
+            input,
+            SchematicsErrorReason::EmptyInput,
+        ));
     }
 
     let mut current_number: Option<PartNumber> = None;
@@ -31,7 +53,10 @@ fn parse(input: &str) -> Result<Schematics, SchematicsError> {
         for (column, symbol) in line.chars().enumerate() {
             if symbol.is_ascii_digit() {
                 if let Some(num) = current_number.as_mut() {
-                    num.add_digit(symbol, column as i32, row as i32);
+                    num.add_digit(symbol, column as i32, row as i32)
+                        .map_err(|reason| {
+                            SchematicsError::new(row + 1, column + 1, line, reason)
+                        })?;
                 } else {
                     current_number = Some(PartNumber::new(symbol, column as i32, row as i32));
                 }
@@ -56,19 +81,66 @@ fn parse(input: &str) -> Result<Schematics, SchematicsError> {
     Ok(symbol_schematics)
 }
 
-#[derive(Debug)]
-pub struct SchematicsError(String);
+#[derive(Debug, PartialEq)]
+pub enum SchematicsErrorReason {
+    EmptyInput,
+    NumberTooLarge,
+    RatioTooLarge,
+    SumTooLarge,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SchematicsError {
+    pub line: usize,
+    pub column: usize,
+    pub source: String,
+    pub reason: SchematicsErrorReason,
+}
+
+impl SchematicsError {
+    fn new(
+        line: usize,
+        column: usize,
+        source: &str,
+        reason: SchematicsErrorReason,
+    ) -> SchematicsError {
+        SchematicsError {
+            line,
+            column,
+            source: source.to_string(),
+            reason,
+        }
+    }
+}
+
+impl Diagnose for SchematicsError {
+    fn diagnostic(&self) -> Diagnostic {
+        match self.reason {
+            SchematicsErrorReason::EmptyInput => Diagnostic::new("Empty input"),
+            SchematicsErrorReason::NumberTooLarge => Diagnostic::new("part number too large")
+                .at(self.line, self.column, 1, &self.source)
+                .with_hint(format!("part numbers must not exceed {}", i32::MAX)),
+            SchematicsErrorReason::RatioTooLarge => Diagnostic::new(format!(
+                "ratio of the gear at line {}, column {} is too large",
+                self.line, self.column
+            )),
+            SchematicsErrorReason::SumTooLarge => {
+                Diagnostic::new("sum of the gear ratios is too large")
+            }
+        }
+    }
+}
 
 impl fmt::Display for SchematicsError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.diagnostic())
     }
 }
 
 impl std::error::Error for SchematicsError {}
 
-#[derive(PartialEq, Debug)]
-struct Schematics {
+#[derive(PartialEq, Debug, Default)]
+pub struct Schematics {
     symbols: HashSet<(i32, i32)>,
     part_numbers: Vec<PartNumber>,
     gears: HashSet<(i32, i32)>,
@@ -102,47 +174,71 @@ impl Schematics {
             .collect()
     }
 
-    pub fn gear_ratios(&self) -> Vec<i32> {
+    pub fn part_numbers_sum(&self) -> usize {
+        self.filter_adjacent()
+            .iter()
+            .map(|part_number| part_number.value())
+            .sum()
+    }
+
+    pub fn gear_ratios_sum(&self) -> Result<usize, SchematicsError> {
+        self.gear_ratios()?
+            .into_iter()
+            .try_fold(0usize, usize::checked_add)
+            .ok_or_else(|| SchematicsError::new(1, 1, "", SchematicsErrorReason::SumTooLarge))
+    }
+
+    pub fn gear_ratios(&self) -> Result<Vec<usize>, SchematicsError> {
         let mut gears: HashMap<(i32, i32), Gear> = HashMap::new();
 
         for part_number in &self.part_numbers {
             for adjacent in &part_number.coordinates {
-                if self.gears.contains(&adjacent) {
-                    let gear = gears.get(&adjacent).unwrap_or(&Gear::Empty);
-                    gears.insert(adjacent.clone(), gear.connect(part_number));
+                if self.gears.contains(adjacent) {
+                    let gear = gears.get(adjacent).unwrap_or(&Gear::Empty);
+                    let gear = gear.connect(part_number).ok_or_else(|| {
+                        let (x, y) = *adjacent;
+                        SchematicsError::new(
+                            y as usize + 1,
+                            x as usize + 1,
+                            "",
+                            SchematicsErrorReason::RatioTooLarge,
+                        )
+                    })?;
+                    gears.insert(*adjacent, gear);
                 }
             }
         }
-        gears
+        Ok(gears
             .values()
             .map(|gear| match gear {
                 Gear::Valid(ratio) => *ratio,
                 _ => 0,
             })
-            .collect()
+            .collect())
     }
 }
 
 #[derive(Copy, Clone)]
 enum Gear {
     Empty,
-    Single(i32),
-    Valid(i32),
+    Single(usize),
+    Valid(usize),
     Invalid,
 }
 
 impl Gear {
-    fn connect(self, other: &PartNumber) -> Self {
+    /// `None` when the ratio overflows.
+    fn connect(self, other: &PartNumber) -> Option<Self> {
         match self {
-            Self::Empty => Self::Single(other.value() as i32),
-            Self::Single(first) => Self::Valid(first * other.value() as i32),
-            Self::Valid(_) | Self::Invalid => Self::Invalid,
+            Self::Empty => Some(Self::Single(other.value())),
+            Self::Single(first) => first.checked_mul(other.value()).map(Self::Valid),
+            Self::Valid(_) | Self::Invalid => Some(Self::Invalid),
         }
     }
 }
 
 #[derive(PartialEq, Debug)]
-struct PartNumber {
+pub struct PartNumber {
     value: i32,
     coordinates: HashSet<(i32, i32)>,
 }
@@ -167,11 +263,16 @@ impl PartNumber {
         result.insert((x + 1, y - 1));
         result
     }
-    pub fn add_digit(&mut self, symbol: char, x: i32, y: i32) {
+    pub fn add_digit(&mut self, symbol: char, x: i32, y: i32) -> Result<(), SchematicsErrorReason> {
         let new_digit = (symbol as u8 - b'0') as i32;
-        self.value = self.value * 10 + new_digit;
+        self.value = self
+            .value
+            .checked_mul(10)
+            .and_then(|value| value.checked_add(new_digit))
+            .ok_or(SchematicsErrorReason::NumberTooLarge)?;
         self.coordinates
-            .extend([(x + 1, y + 1), (x + 1, y), (x + 1, y - 1)])
+            .extend([(x + 1, y + 1), (x + 1, y), (x + 1, y - 1)]);
+        Ok(())
     }
 
     pub fn is_adjacent(&self, other: &HashSet<(i32, i32)>) -> bool {
@@ -207,6 +308,46 @@ mod tests {
         let result = parse("");
         assert!(result.is_err());
     }
+    #[rstest]
+    fn test_any_other_character_is_a_symbol() {
+        assert_eq!(part_1("..\n.1 .").unwrap(), 1);
+        assert_eq!(part_1("7é.").unwrap(), 7);
+    }
+
+    #[rstest]
+    fn test_error_when_part_number_overflows() {
+        let result = part_1("..99999999999*");
+
+        assert_eq!(
+            result,
+            Err(SchematicsError::new(
+                1,
+                12,
+                "..99999999999*",
+                SchematicsErrorReason::NumberTooLarge
+            ))
+        );
+    }
+
+    #[rstest]
+    fn test_large_gear_ratio_does_not_overflow() {
+        assert_eq!(part_2("99999*99999").unwrap(), 99999 * 99999);
+        assert_eq!(
+            part_2("2147483647*2147483647").unwrap(),
+            2147483647 * 2147483647
+        );
+    }
+
+    #[rstest]
+    fn test_error_when_gear_ratios_sum_overflows() {
+        let input = ["2147483647*2147483647"; 5].join("\n.\n");
+
+        assert_eq!(
+            part_2(&input).unwrap_err().reason,
+            SchematicsErrorReason::SumTooLarge
+        );
+    }
+
     #[rstest]
     fn test_parse_single_line_single_symbol(symbols: Schematics) {
         let mut expected = symbols;
@@ -262,7 +403,7 @@ mod tests {
     }
 
     fn adjacent_coordinates(x: i32, y: i32) -> HashSet<(i32, i32)> {
-        return PartNumber::adjacent_coordinates(x, y);
+        PartNumber::adjacent_coordinates(x, y)
     }
 
     #[rstest]
@@ -339,7 +480,7 @@ mod tests {
 
         let result = schematics.unwrap();
 
-        assert_eq!(result.gear_ratios().len(), 0);
+        assert_eq!(result.gear_ratios().unwrap().len(), 0);
     }
 
     #[rstest]
@@ -348,7 +489,7 @@ mod tests {
 
         let result = schematics.unwrap();
 
-        assert_eq!(result.gear_ratios().len(), 1);
-        assert_eq!(result.gear_ratios()[0], 42);
+        assert_eq!(result.gear_ratios().unwrap().len(), 1);
+        assert_eq!(result.gear_ratios().unwrap()[0], 42);
     }
 }
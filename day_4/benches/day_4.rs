@@ -0,0 +1,53 @@
+use aoc_common::bench::{bench_solution, Lcg};
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_4::Day4;
+
+const SAMPLE: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+// Fewer than one match per card on average keeps the part 2 piles bounded.
+const MATCHES: [usize; 8] = [0, 0, 0, 0, 1, 1, 2, 3];
+
+fn generate(cards: usize, seed: u64) -> String {
+    let mut rng = Lcg::new(seed);
+    let mut input = String::new();
+    for id in 1..=cards {
+        let winning: Vec<u64> = (0..10).map(|_| 1 + rng.below(49)).collect();
+        let matches = MATCHES[rng.below(MATCHES.len() as u64) as usize];
+        let chosen: Vec<u64> = (0..25)
+            .map(|index| match index < matches {
+                true => winning[index],
+                false => 50 + rng.below(50),
+            })
+            .collect();
+
+        input.push_str(&format!(
+            "Card {:>5}: {} | {}\n",
+            id,
+            join(&winning),
+            join(&chosen)
+        ));
+    }
+    input
+}
+
+fn join(numbers: &[u64]) -> String {
+    numbers
+        .iter()
+        .map(|number| format!("{:>2}", number))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn benches(c: &mut Criterion) {
+    bench_solution::<Day4>(c, "sample", SAMPLE);
+    bench_solution::<Day4>(c, "large", &generate(10_000, 4));
+    bench_solution::<Day4>(c, "huge", &generate(200_000, 4));
+}
+
+criterion_group!(day_4, benches);
+criterion_main!(day_4);
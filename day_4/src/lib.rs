@@ -1,53 +1,165 @@
-use std::{fmt, num::ParseIntError};
+use aoc_common::diagnostic::{Diagnose, Diagnostic};
+use aoc_common::Solution;
+use std::fmt;
 
-#[derive(Debug)]
-pub struct ScratchingError(String);
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Parsed = Vec<ScratchCard>;
+    type Answer = i32;
+    type Error = ScratchingError;
+
+    fn parse(input: &str) -> Result<Vec<ScratchCard>, ScratchingError> {
+        parse(input)
+    }
+
+    fn part_one(scratch_cards: &Vec<ScratchCard>) -> Result<i32, ScratchingError> {
+        total_score(scratch_cards)
+    }
+
+    fn part_two(scratch_cards: &Vec<ScratchCard>) -> Result<i32, ScratchingError> {
+        total_cards(scratch_cards)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ScratchingErrorReason {
+    EmptyInput,
+    MissingColon,
+    MissingBar,
+    InvalidId,
+    InvalidNumber,
+    AnswerTooLarge,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ScratchingError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub source: String,
+    pub reason: ScratchingErrorReason,
+}
+
+impl ScratchingError {
+    fn new(reason: ScratchingErrorReason, line: &str, token: &str) -> ScratchingError {
+        ScratchingError {
+            line: 1,
+            column: aoc_common::column_of(line, token),
+            text: token.to_string(),
+            source: line.to_string(),
+            reason,
+        }
+    }
+
+    fn at_line(self, line: usize) -> ScratchingError {
+        ScratchingError { line, ..self }
+    }
+}
+
+impl Diagnose for ScratchingError {
+    fn diagnostic(&self) -> Diagnostic {
+        let message = match self.reason {
+            ScratchingErrorReason::EmptyInput => return Diagnostic::new("Empty input"),
+            ScratchingErrorReason::AnswerTooLarge => {
+                return Diagnostic::new("answer too large")
+                    .with_hint(format!("answers must not exceed {}", i32::MAX))
+            }
+            ScratchingErrorReason::MissingColon => "missing `: ` after the card id",
+            ScratchingErrorReason::MissingBar => "missing ` | ` between the numbers",
+            ScratchingErrorReason::InvalidId => "invalid card id",
+            ScratchingErrorReason::InvalidNumber => "invalid number",
+        };
+        Diagnostic::new(message)
+            .at(
+                self.line,
+                self.column,
+                self.text.chars().count(),
+                &self.source,
+            )
+            .with_hint("expected `Card <id>: <numbers> | <numbers>`")
+    }
+}
 
 impl fmt::Display for ScratchingError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.diagnostic())
     }
 }
 
 impl std::error::Error for ScratchingError {}
 
 pub fn part_1(input: &str) -> Result<i32, ScratchingError> {
-    let result = parse(input);
-    match result {
-        Err(ScratchingError(x)) => Err(ScratchingError(x)),
-        Ok(scratch_cards) => Ok(scratch_cards.iter().map(|card| card.score()).sum()),
-    }
+    let scratch_cards = parse(input)?;
+
+    total_score(&scratch_cards)
 }
 
 pub fn part_2(input: &str) -> Result<i32, ScratchingError> {
-    let scratch_cards = parse(input).unwrap();
+    let scratch_cards = parse(input)?;
+
+    total_cards(&scratch_cards)
+}
+
+#[cfg(not(feature = "parallel"))]
+fn total_score(scratch_cards: &[ScratchCard]) -> Result<i32, ScratchingError> {
+    scratch_cards
+        .iter()
+        .try_fold(0i32, |total, card| total.checked_add(card.score()?))
+        .ok_or_else(too_large)
+}
 
-    let mut piles = vec![1usize; scratch_cards.len()];
+#[cfg(feature = "parallel")]
+fn total_score(scratch_cards: &[ScratchCard]) -> Result<i32, ScratchingError> {
+    use rayon::prelude::*;
+    scratch_cards
+        .par_iter()
+        .map(|card| card.score())
+        .try_reduce(|| 0, i32::checked_add)
+        .ok_or_else(too_large)
+}
+
+fn total_cards(scratch_cards: &[ScratchCard]) -> Result<i32, ScratchingError> {
+    let mut piles = vec![1i32; scratch_cards.len()];
     for (index, scratch_card) in scratch_cards.iter().enumerate() {
         let winners = scratch_card.winners() as usize;
-        for i in index + 1..index + 1 + winners {
-            piles[i] += piles[index]
+        let last = (index + 1 + winners).min(piles.len());
+        for i in index + 1..last {
+            piles[i] = piles[i].checked_add(piles[index]).ok_or_else(too_large)?;
         }
     }
 
-    let result = piles.iter().sum::<usize>() as i32;
-    Ok(result)
+    piles
+        .iter()
+        .try_fold(0i32, |total, pile| total.checked_add(*pile))
+        .ok_or_else(too_large)
 }
 
-fn parse(input: &str) -> Result<Vec<ScratchCard>, ScratchingError> {
+fn too_large() -> ScratchingError {
+    ScratchingError::new(ScratchingErrorReason::AnswerTooLarge, "", "")
+}
+
+pub fn parse(input: &str) -> Result<Vec<ScratchCard>, ScratchingError> {
     if input.is_empty() {
-        return Err(ScratchingError("Error: Empty input".to_string()));
+        return Err(ScratchingError::new(
+            ScratchingErrorReason::EmptyInput,
+            input,
+            input,
+        ));
     }
 
     let mut result: Vec<ScratchCard> = Vec::new();
-    for line in input.lines() {
-        let scratch_card = ScratchCard::from_string(line).unwrap();
+    for (index, line) in input.lines().enumerate() {
+        let scratch_card =
+            ScratchCard::from_string(line).map_err(|error| error.at_line(index + 1))?;
         result.push(scratch_card);
     }
     Ok(result)
 }
 #[derive(PartialEq, Debug)]
-struct ScratchCard {
+pub struct ScratchCard {
     id: i32,
     winning_numbers: Vec<i32>,
     chosen_numbers: Vec<i32>,
@@ -55,42 +167,48 @@ struct ScratchCard {
 
 impl ScratchCard {
     pub fn from_string(line: &str) -> Result<ScratchCard, ScratchingError> {
-        let (card_id, numbers) = line.split_once(": ").unwrap();
-        let (winning, chosen) = numbers.split_once(" | ").unwrap();
-        let id = ScratchCard::parse_id(card_id).unwrap();
-
-        let winning_numbers = ScratchCard::parse_numbers(winning).unwrap();
-        let chosen_numbers = ScratchCard::parse_numbers(chosen).unwrap();
+        let (card_id, numbers) = line
+            .split_once(": ")
+            .ok_or_else(|| ScratchingError::new(ScratchingErrorReason::MissingColon, line, line))?;
+        let (winning, chosen) = numbers.split_once(" | ").ok_or_else(|| {
+            ScratchingError::new(ScratchingErrorReason::MissingBar, line, numbers)
+        })?;
+        let id = ScratchCard::parse_id(line, card_id)?;
+
+        let winning_numbers = ScratchCard::parse_numbers(line, winning)?;
+        let chosen_numbers = ScratchCard::parse_numbers(line, chosen)?;
         Ok(ScratchCard {
             id,
             winning_numbers,
             chosen_numbers,
         })
     }
-    fn parse_id(card_id: &str) -> Result<i32, ParseIntError> {
-        let (_, raw_id) = card_id.split_once(' ').unwrap();
-        raw_id.trim().to_string().parse::<i32>()
+    fn parse_id(line: &str, card_id: &str) -> Result<i32, ScratchingError> {
+        let invalid_id =
+            |token: &str| ScratchingError::new(ScratchingErrorReason::InvalidId, line, token);
+        let (_, raw_id) = card_id.split_once(' ').ok_or_else(|| invalid_id(card_id))?;
+        let raw_id = raw_id.trim();
+        raw_id.parse::<i32>().map_err(|_| invalid_id(raw_id))
     }
 
-    fn parse_numbers(input: &str) -> Result<Vec<i32>, ParseIntError> {
+    fn parse_numbers(line: &str, input: &str) -> Result<Vec<i32>, ScratchingError> {
         let mut result = Vec::new();
 
         for raw_number in input.split_whitespace() {
-            result.push(raw_number.trim().parse::<i32>().unwrap());
+            let number = raw_number.parse::<i32>().map_err(|_| {
+                ScratchingError::new(ScratchingErrorReason::InvalidNumber, line, raw_number)
+            })?;
+            result.push(number);
         }
 
         Ok(result)
     }
 
-    pub fn score(&self) -> i32 {
-        let count = self
-            .chosen_numbers
-            .iter()
-            .filter(|number| self.winning_numbers.contains(number))
-            .count() as i32;
-        match count {
-            0 => count,
-            x => 1 << (x - 1),
+    /// `None` when the score does not fit in an `i32`.
+    pub fn score(&self) -> Option<i32> {
+        match self.winners() {
+            0 => Some(0),
+            x => 2i32.checked_pow(x as u32 - 1),
         }
     }
 
@@ -124,20 +242,20 @@ mod tests {
     fn test_score_can_be_zero() {
         let scratch_card = ScratchCard::from_string("Card   1: 23 | 63 9").unwrap();
 
-        assert_eq!(scratch_card.score(), 0);
+        assert_eq!(scratch_card.score(), Some(0));
     }
     #[rstest]
     fn test_can_be_one() {
         let scratch_card = ScratchCard::from_string("Card   1: 23 | 23 9").unwrap();
 
-        assert_eq!(scratch_card.score(), 1);
+        assert_eq!(scratch_card.score(), Some(1));
     }
 
     #[rstest]
     fn test_score_is_doubled_for_more_than_two_matches() {
         let scratch_card = ScratchCard::from_string("Card   1: 23 9 17 | 23 9 17 66").unwrap();
 
-        assert_eq!(scratch_card.score(), 4);
+        assert_eq!(scratch_card.score(), Some(4));
     }
 
     #[rstest]
@@ -170,4 +288,58 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[rstest]
+    #[case::missing_colon("Card 1 23 | 63 9", ScratchingErrorReason::MissingColon, 1)]
+    #[case::missing_bar("Card 1: 23 63 9", ScratchingErrorReason::MissingBar, 9)]
+    #[case::invalid_id("Card x: 23 | 63 9", ScratchingErrorReason::InvalidId, 6)]
+    #[case::invalid_number("Card 1: 23 | 6x 9", ScratchingErrorReason::InvalidNumber, 14)]
+    fn test_parse_error_is_located(
+        #[case] line: &str,
+        #[case] reason: ScratchingErrorReason,
+        #[case] column: usize,
+    ) {
+        let error = ScratchCard::from_string(line).unwrap_err();
+
+        assert_eq!(error.reason, reason);
+        assert_eq!(error.column, column);
+    }
+
+    #[rstest]
+    fn test_parse_error_reports_line_number() {
+        let error = parse("Card 1: 23 | 63 9\nCard 2: 23 | 6x 9").unwrap_err();
+
+        assert_eq!(error.line, 2);
+        assert_eq!(error.text, "6x");
+    }
+
+    #[rstest]
+    fn test_parse_error_renders_snippet() {
+        let error = parse("Card 1: 23 | 63 9\nCard 2: 23 | 6x 9").unwrap_err();
+
+        let expected = "invalid number
+ --> <input>:2:14
+  |
+2 | Card 2: 23 | 6x 9
+  |              ^^
+  = hint: expected `Card <id>: <numbers> | <numbers>`";
+        assert_eq!(error.to_string(), expected);
+    }
+
+    #[rstest]
+    fn test_part_2_propagates_parse_error() {
+        assert!(part_2("Card 1 23 | 63 9").is_err());
+    }
+    #[rstest]
+    fn test_error_when_score_overflows() {
+        let numbers: Vec<_> = (1..=40).map(|n| n.to_string()).collect();
+        let numbers = numbers.join(" ");
+        let card = format!("Card 1: {} | {}", numbers, numbers);
+
+        assert_eq!(ScratchCard::from_string(&card).unwrap().score(), None);
+        assert_eq!(
+            part_1(&card).unwrap_err().reason,
+            ScratchingErrorReason::AnswerTooLarge
+        );
+    }
 }